@@ -0,0 +1,356 @@
+//! The answer of an assignment, either an integer of arbitrary size or a text.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, Mul, Neg};
+
+use crate::parser::ParseError;
+
+/// Digits are stored in base 10^9, which makes printing them in decimal simple.
+const BASE: u64 = 1_000_000_000;
+
+/// A signed integer of arbitrary size.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    /// Least significant digit first, without leading zeros. Zero has no digits.
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    fn from_magnitude(negative: bool, mut digits: Vec<u32>) -> BigInt {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        let negative = negative && !digits.is_empty();
+        BigInt { negative, digits }
+    }
+
+    /// Converts back into an i128, if the value fits.
+    pub fn to_i128(&self) -> Option<i128> {
+        let mut magnitude: u128 = 0;
+        for digit in self.digits.iter().rev() {
+            magnitude = magnitude
+                .checked_mul(BASE as u128)?
+                .checked_add(*digit as u128)?;
+        }
+        if self.negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+}
+
+fn compare_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut digits = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        digits.push((sum % BASE) as u32);
+        carry = sum / BASE;
+    }
+    if carry > 0 {
+        digits.push(carry as u32);
+    }
+    digits
+}
+
+/// Expects `a >= b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut digits = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, digit) in a.iter().enumerate() {
+        let mut difference = *digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if difference < 0 {
+            difference += BASE as i64;
+            borrow = 1;
+        }
+        digits.push(difference as u32);
+    }
+    digits
+}
+
+impl From<u128> for BigInt {
+    fn from(mut value: u128) -> BigInt {
+        let mut digits = vec![];
+        while value > 0 {
+            digits.push((value % BASE as u128) as u32);
+            value /= BASE as u128;
+        }
+        BigInt::from_magnitude(false, digits)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> BigInt {
+        let magnitude = BigInt::from(value.unsigned_abs());
+        BigInt::from_magnitude(value < 0, magnitude.digits)
+    }
+}
+
+macro_rules! big_int_from {
+    ($($unsigned:ty),* ; $($signed:ty),*) => {
+        $(impl From<$unsigned> for BigInt {
+            fn from(value: $unsigned) -> BigInt {
+                BigInt::from(value as u128)
+            }
+        })*
+        $(impl From<$signed> for BigInt {
+            fn from(value: $signed) -> BigInt {
+                BigInt::from(value as i128)
+            }
+        })*
+    };
+}
+
+big_int_from!(u8, u16, u32, u64, usize; i8, i16, i32, i64, isize);
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitude(&self.digits, &other.digits),
+            (true, true) => compare_magnitude(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_magnitude(!self.negative, self.digits)
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_magnitude(
+                self.negative,
+                add_magnitude(&self.digits, &other.digits),
+            );
+        }
+        match compare_magnitude(&self.digits, &other.digits) {
+            Ordering::Less => {
+                BigInt::from_magnitude(other.negative, sub_magnitude(&other.digits, &self.digits))
+            }
+            _ => BigInt::from_magnitude(self.negative, sub_magnitude(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl Mul for BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: BigInt) -> BigInt {
+        let mut digits = vec![0u64; self.digits.len() + other.digits.len() + 1];
+        for (i, a) in self.digits.iter().enumerate() {
+            let mut carry = 0;
+            for (j, b) in other.digits.iter().enumerate() {
+                let product = digits[i + j] + *a as u64 * *b as u64 + carry;
+                digits[i + j] = product % BASE;
+                carry = product / BASE;
+            }
+            let mut k = i + other.digits.len();
+            while carry > 0 {
+                let sum = digits[k] + carry;
+                digits[k] = sum % BASE;
+                carry = sum / BASE;
+                k += 1;
+            }
+        }
+        let digits = digits.into_iter().map(|digit| digit as u32).collect();
+        BigInt::from_magnitude(self.negative != other.negative, digits)
+    }
+}
+
+impl Sum for BigInt {
+    fn sum<I: Iterator<Item = BigInt>>(iter: I) -> BigInt {
+        iter.fold(BigInt::zero(), |sum, value| sum + value)
+    }
+}
+
+impl Product for BigInt {
+    fn product<I: Iterator<Item = BigInt>>(iter: I) -> BigInt {
+        iter.fold(BigInt::from(1u8), |product, value| product * value)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((most_significant, rest)) = self.digits.split_last() else {
+            return write!(f, "0");
+        };
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{most_significant}")?;
+        for digit in rest.iter().rev() {
+            write!(f, "{digit:09}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Integer(BigInt),
+    Text(String),
+}
+
+impl<T: Into<BigInt>> From<T> for Answer {
+    fn from(value: T) -> Answer {
+        Answer::Integer(value.into())
+    }
+}
+
+impl Answer {
+    pub fn text(text: impl Into<String>) -> Answer {
+        Answer::Text(text.into())
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{value}"),
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+/// An intermediate result of an assignment didn't fit into its integer type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Why an assignment couldn't produce an answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    Overflow,
+    /// The input isn't in the format of the puzzle.
+    Parse(ParseError),
+    /// The input is well-formed, but has no answer.
+    Unsolvable(String),
+    /// An argument besides the input, e.g. the bag of day 2, is invalid.
+    InvalidArgument(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Overflow => write!(f, "{Overflow}"),
+            Error::Parse(error) => write!(f, "invalid input: {error}"),
+            Error::Unsolvable(reason) => write!(f, "no solution: {reason}"),
+            Error::InvalidArgument(reason) => write!(f, "invalid argument: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Overflow> for Error {
+    fn from(_: Overflow) -> Error {
+        Error::Overflow
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Error {
+        Error::Parse(error)
+    }
+}
+
+pub type Solution = Result<Answer, Error>;
+
+/// Prints a solution, e.g. `Day 01 - Assignment 01 solution: 142`.
+pub fn print_solution(day: u32, assignment: u32, solution: &Solution) {
+    match solution {
+        Ok(answer) => println!("Day {day:02} - Assignment {assignment:02} solution: {answer}"),
+        Err(error) => println!("Day {day:02} - Assignment {assignment:02} failed: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn big_int_test() {
+        let a = BigInt::from(u128::MAX);
+        let b = BigInt::from(i128::MIN);
+        assert_eq!(a.to_string(), u128::MAX.to_string());
+        assert_eq!(b.to_string(), i128::MIN.to_string());
+        assert_eq!(b.to_i128(), Some(i128::MIN));
+        assert_eq!(a.to_i128(), None);
+
+        let product = a.clone() * a.clone();
+        assert_eq!(
+            product.to_string(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!((a.clone() + b.clone()).to_i128(), Some(i128::MAX));
+        assert_eq!((b.clone() + -b.clone()), BigInt::zero());
+        assert!(b < BigInt::zero() && BigInt::zero() < a);
+        assert_eq!(BigInt::from(-7) * BigInt::from(6), BigInt::from(-42));
+        assert_eq!(
+            BigInt::from(-1_000_000_000) + BigInt::from(1),
+            BigInt::from(-999_999_999)
+        );
+    }
+
+    #[test]
+    fn sum_and_product_test() {
+        for (a, b) in [(-5i64, 3i64), (1_999_999_999, -999_999_999), (0, -1)] {
+            let sum: BigInt = [a, b].into_iter().map(BigInt::from).sum();
+            let product: BigInt = [a, b].into_iter().map(BigInt::from).product();
+            assert_eq!(sum.to_i128(), Some(a as i128 + b as i128));
+            assert_eq!(product.to_i128(), Some(a as i128 * b as i128));
+        }
+    }
+
+    #[test]
+    fn answer_test() {
+        assert_eq!(Answer::from(142).to_string(), "142");
+        assert_eq!(Answer::from(-2i64).to_string(), "-2");
+        assert_eq!(Answer::text("ABC").to_string(), "ABC");
+    }
+}
@@ -1,3 +1,6 @@
+use crate::answer::{self, Overflow, Solution};
+use crate::parser::ParseError;
+
 fn find_first_and_last_digit_01(text: &str) -> (Option<char>, Option<char>) {
     let mut first = None;
     let mut last = None;
@@ -38,89 +41,97 @@ fn get_calibration_value(
     text: &str,
     find_digit_func: fn(&str) -> (Option<char>, Option<char>),
 ) -> Option<u32> {
-    let (first, last) = find_digit_func(&text);
-    match (first, last) {
-        (Some(first), Some(last)) => match (first.to_digit(10), last.to_digit(10)) {
-            (Some(first_digit), Some(last_digit)) => {
-                let calibartion_value = 10 * first_digit + last_digit;
-                Some(calibartion_value)
-            }
-            _ => {
-                eprintln!("Failed to convert char to digit");
-                None
-            }
-        },
-        _ => {
-            eprintln!("Failed to get a number");
-            None
-        }
+    let (first, last) = find_digit_func(text);
+    let first_digit = first?.to_digit(10)?;
+    let last_digit = last?.to_digit(10)?;
+    Some(10 * first_digit + last_digit)
+}
+
+fn missing_digit(index: usize) -> ParseError {
+    ParseError {
+        line: index + 1,
+        column: 1,
+        message: "Expected a line with a digit".to_string(),
     }
 }
 
-fn assignment_01(input: &String) -> u32 {
-    let mut sum = 0;
+pub fn assignment01(input: &str) -> Solution {
+    let mut sum: u32 = 0;
 
-    for entry in input.lines() {
-        match get_calibration_value(&entry, find_first_and_last_digit_01) {
-            Some(value) => sum += value,
-            None => panic!("Expected a value"),
+    for (index, entry) in input.lines().enumerate() {
+        match get_calibration_value(entry, find_first_and_last_digit_01) {
+            Some(value) => sum = sum.checked_add(value).ok_or(Overflow)?,
+            None => return Err(missing_digit(index).into()),
         };
     }
-    sum
+    Ok(sum.into())
 }
 
-fn assignment_02(input: &String) -> u32 {
-    let mut sum = 0;
+pub fn assignment02(input: &str) -> Solution {
+    let mut sum: u32 = 0;
 
-    for entry in input.lines() {
-        match get_calibration_value(&entry, find_first_and_last_digit_02) {
-            Some(value) => {
-                println!("{entry} -> {value}");
-                sum += value
-            }
-            None => panic!("Expected a value"),
+    for (index, entry) in input.lines().enumerate() {
+        match get_calibration_value(entry, find_first_and_last_digit_02) {
+            Some(value) => sum = sum.checked_add(value).ok_or(Overflow)?,
+            None => return Err(missing_digit(index).into()),
         };
     }
-    sum
+    Ok(sum.into())
 }
 
-pub fn day01() {
-    let input = aoc_2023::read_input("input/day01.txt").expect("Failed to read file");
+pub fn day01(input_file: &str) {
+    let input = crate::read_input(input_file).expect("Failed to read file");
 
-    let result_01 = assignment_01(&input);
-    let result_02 = assignment_02(&input);
-
-    println!("Day 01 - Assignment 01 solution: {result_01}");
-    println!("Day 01 - Assignment 02 solution: {result_02}");
+    answer::print_solution(1, 1, &assignment01(&input));
+    answer::print_solution(1, 2, &assignment02(&input));
     println!("\n");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const EXAMPLE_DATA1: &'static str = r"1abc2
-pqr3stu8vwx
-a1b2c3d4e5f
-treb7uchet
-";
-
-    const EXAMPLE_DATA2: &'static str = r"two1nine
-eightwothree
-abcone2threexyz
-xtwone3four
-4nineeightseven2
-zoneight234
-7pqrstsixteen
-";
+    use crate::answer::{Answer, Error};
+    use crate::{generate, property};
+
+    const DIGIT_NAMES: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+
+    /// Looks for a digit or a spelled out digit at every position of every line.
+    fn naive_calibration_sum(input: &str, spelled_out: bool) -> u32 {
+        let mut sum = 0;
+        for line in input.lines() {
+            let mut digits = vec![];
+            for (i, c) in line.char_indices() {
+                if let Some(digit) = c.to_digit(10) {
+                    digits.push(digit);
+                }
+                for (value, name) in (1..).zip(DIGIT_NAMES) {
+                    if spelled_out && line[i..].starts_with(name) {
+                        digits.push(value);
+                    }
+                }
+            }
+            sum += 10 * digits[0] + digits[digits.len() - 1];
+        }
+        sum
+    }
 
     #[test]
-    fn assignment01_test() {
-        assert_eq!(assignment_01(&EXAMPLE_DATA1.to_owned()), 142);
+    fn reference_test() {
+        property::check(100, generate::day01, |input| {
+            let expected = naive_calibration_sum(input, false);
+            assert_eq!(assignment01(input), Ok(Answer::from(expected)));
+            let expected = naive_calibration_sum(input, true);
+            assert_eq!(assignment02(input), Ok(Answer::from(expected)));
+        });
     }
 
     #[test]
-    fn assignment02_test() {
-        assert_eq!(assignment_02(&EXAMPLE_DATA2.to_owned()), 281);
+    fn missing_digit_test() {
+        for assignment in [assignment01, assignment02] {
+            let error = assignment("1abc2\nxyz\n").unwrap_err();
+            assert!(matches!(error, Error::Parse(ParseError { line: 2, .. })));
+        }
     }
 }
@@ -1,35 +1,116 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::answer::{self, Error, Overflow, Solution};
+use crate::parser::{
+    self, labelled_line, number, opt, pair, preceded, separated_list, space0, space1, tag,
+    terminated, word, ParseError, Parser,
+};
+
+/// The bag from the puzzle: 12 red, 13 green and 14 blue cubes.
+pub const PUZZLE_BAG: &str = "red=12,green=13,blue=14";
+
+/// An interned cube color, its name is stored in the `Palette` that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CubeColor(usize);
+
+/// All cube colors that have been seen so far, in the order they were discovered.
 #[derive(Debug, Default)]
-pub enum CubeColor {
-    Red,
-    Green,
-    Blue,
-    #[default]
-    Empty,
-}
-
-impl CubeColor {
-    fn from(color_as_string: &str) -> CubeColor {
-        match color_as_string {
-            "red" => CubeColor::Red,
-            "green" => CubeColor::Green,
-            "blue" => CubeColor::Blue,
-            _ => CubeColor::Empty,
+pub struct Palette {
+    names: Vec<String>,
+    colors: HashMap<String, CubeColor>,
+}
+
+impl Palette {
+    pub fn intern(&mut self, name: &str) -> CubeColor {
+        if let Some(color) = self.colors.get(name) {
+            return *color;
         }
+        let color = CubeColor(self.names.len());
+        self.names.push(name.to_owned());
+        self.colors.insert(name.to_owned(), color);
+        color
+    }
+
+    pub fn name(&self, color: CubeColor) -> &str {
+        &self.names[color.0]
+    }
+
+    pub fn colors(&self) -> impl Iterator<Item = CubeColor> {
+        (0..self.names.len()).map(CubeColor)
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Cube {
-    amount: u32,
-    color: CubeColor,
+    pub amount: u32,
+    pub color: CubeColor,
 }
 
 impl Cube {
-    fn new(amount: u32, color: CubeColor) -> Cube {
+    pub fn new(amount: u32, color: CubeColor) -> Cube {
         Cube { amount, color }
     }
 }
 
+/// The amount of cubes per color that is loaded into the bag.
+/// Colors which are not part of the bag have a capacity of zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bag {
+    limits: BTreeMap<CubeColor, u32>,
+}
+
+impl Bag {
+    /// Parses a bag in the form of `red=12,green=13,blue=14`.
+    pub fn parse(text: &str, palette: &mut Palette) -> Result<Bag, &'static str> {
+        let mut bag = Bag::default();
+        for entry in text.split_terminator(',') {
+            let (color, amount) = entry.split_once('=').ok_or("Expected color=amount")?;
+            let color = color.trim();
+            if color.is_empty() {
+                return Err("Missing cube color");
+            }
+            let amount = amount
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| "Invalid cube amount")?;
+            bag = bag.with(palette.intern(color), amount);
+        }
+        Ok(bag)
+    }
+
+    pub fn with(mut self, color: CubeColor, amount: u32) -> Bag {
+        self.limits.insert(color, amount);
+        self
+    }
+
+    pub fn capacity(&self, color: CubeColor) -> u32 {
+        self.limits.get(&color).copied().unwrap_or(0)
+    }
+
+    /// The smallest bag with which every given game would have been possible.
+    pub fn minimal(games: &[Game]) -> Bag {
+        let mut bag = Bag::default();
+        for game in games {
+            for (color, amount) in game.get_lowest_possible_cubes() {
+                let capacity = bag.limits.entry(color).or_insert(0);
+                if amount > *capacity {
+                    *capacity = amount;
+                }
+            }
+        }
+        bag
+    }
+
+    pub fn format(&self, palette: &Palette) -> String {
+        let entries: Vec<String> = self
+            .limits
+            .iter()
+            .map(|(color, amount)| format!("{}={amount}", palette.name(*color)))
+            .collect();
+        entries.join(",")
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct GameRecord {
     pub shown_cubes: Vec<Cube>,
@@ -46,176 +127,268 @@ impl Game {
         Game { id, record }
     }
 
-    pub fn is_possible(&self) -> bool {
-        const MAX_RED_CUBES: u32 = 12;
-        const MAX_GREEN_CUBES: u32 = 13;
-        const MAX_BLUE_CUBES: u32 = 14;
-
-        let mut is_possible = true;
-        for cube in &self.record.shown_cubes {
-            match cube.color {
-                CubeColor::Red => {
-                    if cube.amount > MAX_RED_CUBES {
-                        is_possible = false;
-                        break;
-                    }
-                }
-                CubeColor::Green => {
-                    if cube.amount > MAX_GREEN_CUBES {
-                        is_possible = false;
-                        break;
-                    }
-                }
-                CubeColor::Blue => {
-                    if cube.amount > MAX_BLUE_CUBES {
-                        is_possible = false;
-                        break;
-                    }
-                }
-                _ => panic!("Unknown cube"),
-            }
-        }
-        is_possible
+    pub fn is_possible(&self, bag: &Bag) -> bool {
+        self.record
+            .shown_cubes
+            .iter()
+            .all(|cube| cube.amount <= bag.capacity(cube.color))
     }
 
-    pub fn get_lowest_possible_cubes(&self) -> (u32, u32, u32) {
-        let mut max_red: u32 = 0;
-        let mut max_green: u32 = 0;
-        let mut max_blue: u32 = 0;
+    pub fn get_lowest_possible_cubes(&self) -> HashMap<CubeColor, u32> {
+        let mut lowest: HashMap<CubeColor, u32> = HashMap::new();
 
         for cube in &self.record.shown_cubes {
-            match cube.color {
-                CubeColor::Red => {
-                    if cube.amount > max_red {
-                        max_red = cube.amount;
-                    }
-                }
-                CubeColor::Green => {
-                    if cube.amount > max_green {
-                        max_green = cube.amount;
-                    }
-                }
-                CubeColor::Blue => {
-                    if cube.amount > max_blue {
-                        max_blue = cube.amount;
-                    }
-                }
-                _ => panic!("Unknown cube"),
+            let amount = lowest.entry(cube.color).or_insert(0);
+            if cube.amount > *amount {
+                *amount = cube.amount;
             }
         }
-
-        (max_red, max_green, max_blue)
+        lowest
     }
-}
 
-fn extract_game_id(id_string: &str) -> u32 {
-    let it = id_string.split_whitespace();
-    let id = it.last().map(|id| match id.parse::<u32>() {
-        Ok(id) => id,
-        Err(_) => panic!("Invalid id string"),
-    });
-    id.unwrap()
+    /// The product of the lowest possible cubes over all colors of the palette.
+    /// A color that was never shown in this game makes the power zero.
+    /// Returns `None` if the power doesn't fit into an u32.
+    pub fn power(&self, palette: &Palette) -> Option<u32> {
+        let lowest = self.get_lowest_possible_cubes();
+        palette
+            .colors()
+            .map(|color| lowest.get(&color).copied().unwrap_or(0))
+            .try_fold(1u32, |power, amount| power.checked_mul(amount))
+    }
 }
 
-fn extract_game_record(record_string: &str) -> GameRecord {
-    let mut game_record = GameRecord::default();
+type GameLine<'a> = (u32, Vec<Vec<(u32, &'a str)>>);
 
-    let record_sets = record_string.split_terminator(';');
-    for set in record_sets {
-        let cube_str = set.split_terminator(',');
-        for str in cube_str {
-            let digit_color_pair = str.split_whitespace();
+/// `Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green`
+fn game_line<'a>() -> impl Parser<'a, GameLine<'a>> {
+    let id = preceded(pair(tag("Game"), space1()), number::<u32>());
+    let cube = pair(terminated(number::<u32>(), space1()), word());
+    let set = separated_list(pair(tag(","), space0()), cube);
+    let sets = terminated(separated_list(pair(tag(";"), space0()), set), opt(tag(";")));
+    labelled_line(id, sets)
+}
 
-            let mut amount: Option<u32> = None;
-            let mut color: Option<CubeColor> = None;
-            for entry in digit_color_pair {
-                match entry.parse::<u32>() {
-                    Ok(digit) => amount = Some(digit),
-                    Err(_) => color = Some(CubeColor::from(entry)),
-                }
-            }
-            if let (Some(digit), Some(color)) = (amount, color) {
-                game_record.shown_cubes.push(Cube::new(digit, color));
-            }
+fn build_game((id, sets): GameLine<'_>, palette: &mut Palette) -> Game {
+    let mut record = GameRecord::default();
+    for set in sets {
+        for (amount, color) in set {
+            record
+                .shown_cubes
+                .push(Cube::new(amount, palette.intern(color)));
         }
     }
-    game_record
+    Game::new(id, record)
 }
 
-pub fn extract_game_information(text: &str) -> Game {
-    let mut game_entry_it = text.split_terminator(':');
-
-    let mut id = 0;
-    if let Some(id_string) = game_entry_it.next() {
-        id = extract_game_id(id_string);
-    }
-
-    let mut record = GameRecord::default();
-    if let Some(record_string) = game_entry_it.next() {
-        record = extract_game_record(record_string);
-    }
+pub(crate) fn extract_game_information(
+    text: &str,
+    palette: &mut Palette,
+) -> Result<Game, ParseError> {
+    let game = parser::parse_all(game_line(), text)?;
+    Ok(build_game(game, palette))
+}
 
-    Game::new(id, record)
+/// Returns all games which would have been possible with the given bag.
+pub fn feasible_games<'a>(games: &'a [Game], bag: &Bag) -> Vec<&'a Game> {
+    games.iter().filter(|game| game.is_possible(bag)).collect()
 }
 
-fn assignment01(input: &String) -> u32 {
+pub fn extract_games(input: &str, palette: &mut Palette) -> Result<Vec<Game>, ParseError> {
     let mut games: Vec<Game> = Vec::new();
-    for line in input.lines() {
-        games.push(extract_game_information(line));
+    for (index, line) in input.lines().enumerate() {
+        let game = extract_game_information(line, palette).map_err(|error| ParseError {
+            line: index + 1,
+            ..error
+        })?;
+        games.push(game);
     }
+    Ok(games)
+}
 
-    let mut sum = 0;
-    for game in games {
-        if game.is_possible() {
-            sum += game.id;
-        }
+pub fn assignment01(input: &str, bag: &str) -> Solution {
+    let mut palette = Palette::default();
+    let games = extract_games(input, &mut palette)?;
+    let bag = Bag::parse(bag, &mut palette)
+        .map_err(|message| Error::InvalidArgument(format!("bag '{bag}': {message}")))?;
+
+    let mut sum: u32 = 0;
+    for game in feasible_games(&games, &bag) {
+        sum = sum.checked_add(game.id).ok_or(Overflow)?;
     }
-    sum
+    Ok(sum.into())
 }
 
-fn assignment02(input: &String) -> u32 {
-    let mut games: Vec<Game> = Vec::new();
-    for line in input.lines() {
-        games.push(extract_game_information(line));
-    }
+pub fn assignment02(input: &str) -> Solution {
+    let mut palette = Palette::default();
+    let games = extract_games(input, &mut palette)?;
 
-    let mut sum = 0;
+    let mut sum: u32 = 0;
     for game in games {
-        let (max_red, max_green, max_blue) = game.get_lowest_possible_cubes();
-        let pow = max_red * max_green * max_blue;
-        sum += pow;
+        let power = game.power(&palette).ok_or(Overflow)?;
+        sum = sum.checked_add(power).ok_or(Overflow)?;
     }
-    sum
+    Ok(sum.into())
 }
 
-pub fn day02() {
-    let input = aoc_2023::read_input("input/day02.txt").expect("Failed to read file");
+pub fn day02(input_file: &str, bag: &str) {
+    let input = crate::read_input(input_file).expect("Failed to read file");
 
-    let result_01 = assignment01(&input);
-    let result_02 = assignment02(&input);
+    answer::print_solution(2, 1, &assignment01(&input, bag));
+    answer::print_solution(2, 2, &assignment02(&input));
 
-    println!("Day 02 - Assignment 01 solution: {result_01}");
-    println!("Day 02 - Assignment 02 solution: {result_02}");
+    let mut palette = Palette::default();
+    if let Ok(games) = extract_games(&input, &mut palette) {
+        println!(
+            "Day 02 - Minimal bag for all games: {}",
+            Bag::minimal(&games).format(&palette)
+        );
+    }
     println!("\n");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answer::Answer;
+    use crate::{generate, property};
+
+    const EXAMPLE_DATA: &str = include_str!("../../fixtures/day02/example.txt");
+
+    #[test]
+    fn extract_game_information_test() {
+        let mut palette = Palette::default();
+        let game = extract_game_information("Game 12: 3 blue, 4 red; 1 red", &mut palette).unwrap();
+        assert_eq!(game.id, 12);
+        assert_eq!(game.record.shown_cubes.len(), 3);
 
-    const EXAMPLE_DATA: &'static str = r"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green;
+        let error = extract_game_information("Game 12: 3 blue 4 red", &mut palette).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 17));
+    }
+
+    #[test]
+    fn parse_bag_test() {
+        let mut palette = Palette::default();
+        let bag = Bag::parse("red=12, green=13, blue=14", &mut palette).unwrap();
+        assert_eq!(bag.format(&palette), PUZZLE_BAG);
+        assert_eq!(bag.capacity(palette.intern("green")), 13);
+        assert_eq!(bag.capacity(palette.intern("purple")), 0);
+        assert!(Bag::parse("=3", &mut palette).is_err());
+        assert!(Bag::parse("red=many", &mut palette).is_err());
+
+        let error = Error::InvalidArgument("bag 'red=many': Invalid cube amount".to_owned());
+        assert_eq!(assignment01(EXAMPLE_DATA, "red=many"), Err(error));
+    }
+
+    #[test]
+    fn minimal_bag_test() {
+        let mut palette = Palette::default();
+        let games = extract_games(EXAMPLE_DATA, &mut palette).unwrap();
+        let bag = Bag::minimal(&games);
+        assert_eq!(bag.format(&palette), "blue=15,red=20,green=13");
+
+        let ids: Vec<u32> = feasible_games(&games, &bag).iter().map(|g| g.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+
+        let small_bag = Bag::parse("red=4,green=3,blue=6", &mut palette).unwrap();
+        let ids: Vec<u32> = feasible_games(&games, &small_bag)
+            .iter()
+            .map(|g| g.id)
+            .collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn additional_colors_test() {
+        const DATA: &str = r"Game 1: 2 red, 3 purple; 1 green, 4 blue
+Game 2: 1 red, 1 green, 1 blue, 2 purple
+Game 3: 5 red, 5 green, 5 blue
 ";
+        let mut palette = Palette::default();
+        let games = extract_games(DATA, &mut palette).unwrap();
+        assert_eq!(palette.colors().count(), 4);
+        assert_eq!(games[0].power(&palette), Some(24));
+        assert_eq!(games[2].power(&palette), Some(0));
+        assert_eq!(assignment01(DATA, PUZZLE_BAG), Ok(Answer::from(3)));
+        assert_eq!(
+            assignment01(DATA, "red=5,green=5,blue=5,purple=3"),
+            Ok(Answer::from(6))
+        );
+    }
+
+    /// Splits a game line into its id and all shown cubes.
+    fn naive_game(line: &str) -> (u32, Vec<(u32, &str)>) {
+        let (id, sets) = line
+            .strip_prefix("Game ")
+            .unwrap()
+            .split_once(": ")
+            .unwrap();
+        let cubes = sets
+            .split("; ")
+            .flat_map(|set| set.split(", "))
+            .map(|cube| {
+                let (amount, color) = cube.split_once(' ').unwrap();
+                (amount.parse().unwrap(), color)
+            })
+            .collect();
+        (id.parse().unwrap(), cubes)
+    }
 
     #[test]
-    fn assignment01_test() {
-        assert_eq!(assignment01(&EXAMPLE_DATA.to_owned()), 8);
+    fn round_trip_test() {
+        property::check(100, generate::day02, |input| {
+            let mut palette = Palette::default();
+            let games = extract_games(input, &mut palette).unwrap();
+            assert_eq!(games.len(), input.lines().count());
+
+            for (game, line) in games.iter().zip(input.lines()) {
+                let cubes: Vec<(u32, &str)> = game
+                    .record
+                    .shown_cubes
+                    .iter()
+                    .map(|cube| (cube.amount, palette.name(cube.color)))
+                    .collect();
+                assert_eq!((game.id, cubes), naive_game(line));
+            }
+        });
     }
+
     #[test]
-    fn assignment02_test() {
-        assert_eq!(assignment02(&EXAMPLE_DATA.to_owned()), 2286);
+    fn reference_test() {
+        property::check(100, generate::day02, |input| {
+            // the power only takes the colors into account that appear in the input
+            let mut colors: Vec<&str> = input
+                .lines()
+                .flat_map(|line| naive_game(line).1)
+                .map(|(_, color)| color)
+                .collect();
+            colors.sort();
+            colors.dedup();
+
+            let mut possible = 0;
+            let mut power = 0;
+            for line in input.lines() {
+                let (id, cubes) = naive_game(line);
+                let limit = |color| match color {
+                    "red" => 12,
+                    "green" => 13,
+                    _ => 14,
+                };
+                if cubes.iter().all(|(amount, color)| *amount <= limit(color)) {
+                    possible += id;
+                }
+                let fewest = |name| {
+                    cubes
+                        .iter()
+                        .filter(|(_, color)| *color == name)
+                        .map(|(amount, _)| *amount)
+                        .max()
+                        .unwrap_or(0)
+                };
+                power += colors.iter().map(|color| fewest(*color)).product::<u32>();
+            }
+            assert_eq!(assignment01(input, PUZZLE_BAG), Ok(Answer::from(possible)));
+            assert_eq!(assignment02(input), Ok(Answer::from(power)));
+        });
     }
 }
@@ -1,127 +1,292 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::IsTerminal;
+
+use crate::answer::{self, Overflow, Solution};
+use crate::parser::ParseError;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
-struct Cell {
-    value: char,
-    row: usize,
-    col: usize,
+pub struct Cell {
+    pub value: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// The symbol and the number of parts which make a gear in the puzzle.
+pub const GEAR_SYMBOL: char = '*';
+pub const GEAR_PARTS: usize = 2;
+
+/// A symbol touching exactly the expected number of distinct part numbers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Gear {
+    /// Row and column of the symbol.
+    pub position: (usize, usize),
+    pub parts: Vec<u32>,
+}
+
+impl Gear {
+    /// The product of all parts, or `None` if it doesn't fit into an u64.
+    pub fn ratio(&self) -> Option<u64> {
+        self.parts
+            .iter()
+            .try_fold(1u64, |ratio, part| ratio.checked_mul(*part as u64))
+    }
+}
+
+/// A number of the schematic together with the cells of its digits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartNumber {
+    pub value: u32,
+    pub cells: Vec<Cell>,
 }
 
+/// The bipartite graph between the symbols and the numbers they touch.
+/// Symbols and numbers are referred to by their index.
 #[derive(Clone, Debug)]
-struct Grid {
-    cells: Vec<Vec<Cell>>,
+pub struct Adjacency {
+    symbols: Vec<Cell>,
+    numbers: Vec<PartNumber>,
+    numbers_of_symbol: Vec<Vec<usize>>,
+    symbols_of_number: Vec<Vec<usize>>,
 }
 
-impl Grid {
-    fn build_from_input(input: &str) -> Grid {
-        let cells: Vec<Vec<Cell>> = input
-            .lines()
-            .enumerate()
-            .map(|(row, line)| {
-                line.chars()
-                    .enumerate()
-                    .map(move |(col, value)| Cell { value, row, col })
-                    .collect()
+impl Adjacency {
+    pub fn symbols(&self) -> &[Cell] {
+        &self.symbols
+    }
+
+    pub fn numbers(&self) -> &[PartNumber] {
+        &self.numbers
+    }
+
+    /// The indices of the distinct numbers touching the symbol.
+    pub fn numbers_of(&self, symbol: usize) -> &[usize] {
+        &self.numbers_of_symbol[symbol]
+    }
+
+    /// The indices of the symbols touching the number.
+    pub fn symbols_of(&self, number: usize) -> &[usize] {
+        &self.symbols_of_number[number]
+    }
+
+    /// The values of the numbers touching at least one symbol.
+    pub fn part_numbers(&self) -> Vec<u32> {
+        (0..self.numbers.len())
+            .filter(|number| !self.symbols_of(*number).is_empty())
+            .map(|number| self.numbers[number].value)
+            .collect()
+    }
+
+    /// Every `symbol` which touches exactly `parts` distinct numbers.
+    pub fn gears(&self, symbol: char, parts: usize) -> Vec<Gear> {
+        (0..self.symbols.len())
+            .filter(|index| self.symbols[*index].value == symbol)
+            .filter(|index| self.numbers_of(*index).len() == parts)
+            .map(|index| {
+                let cell = self.symbols[index];
+                Gear {
+                    position: (cell.row, cell.col),
+                    parts: self
+                        .numbers_of(index)
+                        .iter()
+                        .map(|number| self.numbers[*number].value)
+                        .collect(),
+                }
             })
-            .collect();
+            .collect()
+    }
 
-        Grid { cells }
+    /// The numbers which aren't part numbers, because they touch no symbol.
+    pub fn isolated_numbers(&self) -> Vec<&PartNumber> {
+        (0..self.numbers.len())
+            .filter(|number| self.symbols_of(*number).is_empty())
+            .map(|number| &self.numbers[number])
+            .collect()
     }
 
-    fn get_row_size(&self) -> usize {
-        self.cells.len()
+    /// The symbols touching at least `count` distinct numbers.
+    pub fn symbols_touching_at_least(&self, count: usize) -> Vec<Cell> {
+        (0..self.symbols.len())
+            .filter(|symbol| self.numbers_of(*symbol).len() >= count)
+            .map(|symbol| self.symbols[symbol])
+            .collect()
     }
 
-    fn get_col_size(&self) -> usize {
-        if self.cells.len() > 0 {
-            self.cells[0].len()
-        } else {
-            0
+    /// For every kind of symbol, the sum of all numbers touching a symbol of that kind.
+    /// A number touching two symbols of the same kind only counts once.
+    pub fn totals_by_symbol(&self) -> BTreeMap<char, u64> {
+        let mut totals: BTreeMap<char, u64> = BTreeMap::new();
+        for (number, symbols) in self.symbols_of_number.iter().enumerate() {
+            let mut kinds: Vec<char> = symbols
+                .iter()
+                .map(|symbol| self.symbols[*symbol].value)
+                .collect();
+            kinds.sort();
+            kinds.dedup();
+            for kind in kinds {
+                *totals.entry(kind).or_default() += self.numbers[number].value as u64;
+            }
         }
+        totals
     }
+}
+
+/// How a rendered schematic marks its numbers and symbols.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Part numbers in green, ignored numbers dimmed, symbols in magenta and gears in yellow.
+    Ansi,
+    /// Part numbers as `[467]`, ignored numbers as `(114)`, symbols as `<#>` and gears as `{*}`.
+    Plain,
+}
 
-    fn get(&self, row: usize, col: usize) -> Option<Cell> {
-        self.cells.get(row)?.get(col).cloned()
+impl Style {
+    /// Colours if stdout is a terminal, unless `NO_COLOR` is set.
+    pub fn detect() -> Style {
+        if std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+            Style::Ansi
+        } else {
+            Style::Plain
+        }
     }
+}
 
-    fn get_adjacent_cells(&self, row: usize, col: usize) -> Vec<Cell> {
-        let mut adjacent_cells: Vec<Cell> = Vec::new();
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mark {
+    Part,
+    Ignored,
+    Symbol,
+    Gear,
+}
 
-        for i in (row.saturating_sub(1))..=(row + 1).min(self.get_row_size() - 1) {
-            for j in (col.saturating_sub(1))..=(col + 1).min(self.get_row_size() - 1) {
-                if i != row || j != col {
-                    if let Some(cell) = self.get(i, j) {
-                        adjacent_cells.push(cell);
-                    }
-                }
+impl Mark {
+    fn paint(self, text: &str, style: Style) -> String {
+        match style {
+            Style::Ansi => {
+                let color = match self {
+                    Mark::Part => "32",
+                    Mark::Ignored => "2",
+                    Mark::Symbol => "35",
+                    Mark::Gear => "1;33",
+                };
+                format!("\x1b[{color}m{text}\x1b[0m")
+            }
+            Style::Plain => {
+                let (open, close) = match self {
+                    Mark::Part => ('[', ']'),
+                    Mark::Ignored => ('(', ')'),
+                    Mark::Symbol => ('<', '>'),
+                    Mark::Gear => ('{', '}'),
+                };
+                format!("{open}{text}{close}")
             }
         }
+    }
+}
 
-        adjacent_cells
+/// What to do with rows which are shorter or longer than the first one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RaggedRows {
+    Reject,
+    /// Fills up all rows with `.` to the length of the longest one.
+    Pad,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridOptions {
+    pub ragged_rows: RaggedRows,
+    /// Removes a `\r` at the end of every line and drops the blank lines at the end.
+    pub trim: bool,
+}
+
+impl Default for GridOptions {
+    fn default() -> GridOptions {
+        GridOptions {
+            ragged_rows: RaggedRows::Reject,
+            trim: true,
+        }
     }
+}
 
-    fn is_adjacent_to_symbol(&self, row: usize, col: usize) -> bool {
-        let mut is_adjacent_to_symbol = false;
-        if let Some(cell) = self.get(row, col) {
-            if cell.value.is_digit(10) {
-                let adjacent_cells = self.get_adjacent_cells(row, col);
-                for cell in adjacent_cells {
-                    if cell.value != '.' && !cell.value.is_digit(10) {
-                        is_adjacent_to_symbol = true;
-                    }
-                }
+#[derive(Clone, Debug)]
+pub struct Grid {
+    /// The values of the cells, their position is given by the indices.
+    rows: Vec<Vec<char>>,
+}
+
+impl Grid {
+    /// Builds the grid, checking that all rows have the same length.
+    pub fn parse(input: &str, options: GridOptions) -> Result<Grid, ParseError> {
+        let mut lines: Vec<&str> = input.split('\n').collect();
+        // a line break ends the last line instead of starting an empty one
+        if input.ends_with('\n') || input.is_empty() {
+            lines.pop();
+        }
+        if options.trim {
+            for line in lines.iter_mut() {
+                *line = line.strip_suffix('\r').unwrap_or(line);
+            }
+            while lines.last().is_some_and(|line| line.trim().is_empty()) {
+                lines.pop();
             }
         }
-        is_adjacent_to_symbol
-    }
 
-    fn is_adjacent_to_number(&self, row: usize, col: usize, symbol: char) -> bool {
-        let mut is_adjacent_to_number = 0;
-        if let Some(cell) = self.get(row, col) {
-            if cell.value == symbol {
-                let adjacent_cells = self.get_adjacent_cells(row, col);
-                for cell in adjacent_cells {
-                    if cell.value.is_digit(10) {
-                        is_adjacent_to_number += 1;
-                    }
-                }
+        let width = match options.ragged_rows {
+            RaggedRows::Reject => lines.first().map_or(0, |line| line.chars().count()),
+            RaggedRows::Pad => lines
+                .iter()
+                .map(|line| line.chars().count())
+                .max()
+                .unwrap_or(0),
+        };
+
+        let mut rows: Vec<Vec<char>> = Vec::with_capacity(lines.len());
+        for (row, line) in lines.iter().enumerate() {
+            let mut values: Vec<char> = line.chars().collect();
+            if values.len() != width && options.ragged_rows == RaggedRows::Reject {
+                return Err(ParseError {
+                    line: row + 1,
+                    column: width.min(values.len()) + 1,
+                    message: format!("Expected a row of {width} cells, found {}", values.len()),
+                });
             }
+            values.resize(width, '.');
+            rows.push(values);
         }
 
-        is_adjacent_to_number >= 2
+        Ok(Grid { rows })
     }
 
-    fn get_adjacent_numbers(&self, row: usize, col: usize) -> Vec<u32> {
-        let mut part_number_cells: Vec<Vec<Cell>> = Vec::new();
-        if let Some(_) = self.get(row, col) {
-            let adjacent_cells = self.get_adjacent_cells(row, col);
-            for cell in adjacent_cells {
-                if cell.value.is_digit(10) {
-                    let part_number = self.get_part_number_cells(cell.row, cell.col);
-                    let mut already_considered = false;
-                    for cell in &part_number_cells {
-                        if cell.contains(&part_number[0]) {
-                            already_considered = true;
-                        }
-                    }
-                    if !already_considered {
-                        part_number_cells.push(part_number);
+    pub fn get_row_size(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn get_col_size(&self) -> usize {
+        if !self.rows.is_empty() {
+            self.rows[0].len()
+        } else {
+            0
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<Cell> {
+        let value = *self.rows.get(row)?.get(col)?;
+        Some(Cell { value, row, col })
+    }
+
+    fn get_adjacent_cells(&self, row: usize, col: usize) -> Vec<Cell> {
+        let mut adjacent_cells: Vec<Cell> = Vec::new();
+
+        for i in (row.saturating_sub(1))..=(row + 1).min(self.get_row_size() - 1) {
+            for j in (col.saturating_sub(1))..=(col + 1).min(self.get_col_size() - 1) {
+                if i != row || j != col {
+                    if let Some(cell) = self.get(i, j) {
+                        adjacent_cells.push(cell);
                     }
                 }
             }
         }
-        let mut part_numbers: Vec<u32> = Vec::new();
-        for part_number in part_number_cells {
-            let mut part_number_string = String::new();
-            for part in part_number {
-                part_number_string.push(part.value);
-            }
-            if let Ok(number) = part_number_string.parse::<u32>() {
-                part_numbers.push(number);
-            } else {
-                panic!("Value should be an integer");
-            }
-        }
 
-        part_numbers
+        adjacent_cells
     }
 
     fn find_start_index(&self, row: usize, col: usize) -> usize {
@@ -131,7 +296,7 @@ impl Grid {
             current_col -= 1;
 
             if let Some(cell) = self.get(row, current_col) {
-                if !cell.value.is_digit(10) {
+                if !cell.value.is_ascii_digit() {
                     current_col += 1;
                     break;
                 }
@@ -147,7 +312,7 @@ impl Grid {
             current_col += 1;
 
             if let Some(cell) = self.get(row, current_col) {
-                if !cell.value.is_digit(10) {
+                if !cell.value.is_ascii_digit() {
                     current_col -= 1;
                     break;
                 }
@@ -169,7 +334,7 @@ impl Grid {
                 current_col += 1;
 
                 if let Some(cell) = self.get(row, current_col) {
-                    if cell.value.is_digit(10) {
+                    if cell.value.is_ascii_digit() {
                         part_number_cells.push(cell);
                     } else {
                         break;
@@ -181,132 +346,405 @@ impl Grid {
         part_number_cells
     }
 
-    fn get_part_numbers(&self) -> Vec<u32> {
-        let mut part_numbers_cells: Vec<Vec<Cell>> = Vec::new();
+    /// Builds the graph between all symbols and all numbers of the schematic.
+    pub fn adjacency(&self) -> Result<Adjacency, Overflow> {
+        // sorted by the position of the first digit, which is used to look them up
+        let mut numbers: Vec<PartNumber> = Vec::new();
+        let mut symbols: Vec<Cell> = Vec::new();
 
         for row in 0..self.get_row_size() {
             for col in 0..self.get_col_size() {
                 if let Some(cell) = self.get(row, col) {
-                    if self.is_adjacent_to_symbol(cell.row, cell.col) {
-                        let part_number_cells = self.get_part_number_cells(cell.row, cell.col);
-                        let mut already_considered = false;
-                        for cell in &part_numbers_cells {
-                            // if the first index is already part of the collection
-                            // this number was already considered
-                            if cell.contains(&part_number_cells[0]) {
-                                already_considered = true;
-                            }
-                        }
-                        if !already_considered {
-                            part_numbers_cells.push(part_number_cells);
+                    if cell.value.is_ascii_digit() {
+                        if self.find_start_index(row, col) == col {
+                            let cells = self.get_part_number_cells(row, col);
+                            let digits: String = cells.iter().map(|cell| cell.value).collect();
+                            let value = digits.parse().map_err(|_| Overflow)?;
+                            numbers.push(PartNumber { value, cells });
                         }
+                    } else if cell.value != '.' {
+                        symbols.push(cell);
                     }
                 }
             }
         }
 
-        let mut part_numbers: Vec<u32> = Vec::new();
-        for part_number_cells in part_numbers_cells {
-            let mut part_number_string = String::new();
-            for cell in part_number_cells {
-                part_number_string.push(cell.value);
-            }
-            if let Ok(number) = part_number_string.parse::<u32>() {
-                part_numbers.push(number);
-            } else {
-                panic!("Value should be an integer");
+        let mut numbers_of_symbol: Vec<Vec<usize>> = vec![Vec::new(); symbols.len()];
+        let mut symbols_of_number: Vec<Vec<usize>> = vec![Vec::new(); numbers.len()];
+        for (symbol, cell) in symbols.iter().enumerate() {
+            for adjacent in self.get_adjacent_cells(cell.row, cell.col) {
+                if adjacent.value.is_ascii_digit() {
+                    let start = (
+                        adjacent.row,
+                        self.find_start_index(adjacent.row, adjacent.col),
+                    );
+                    let number = numbers.partition_point(|number| {
+                        let first = number.cells[0];
+                        (first.row, first.col) < start
+                    });
+                    if !numbers_of_symbol[symbol].contains(&number) {
+                        numbers_of_symbol[symbol].push(number);
+                        symbols_of_number[number].push(symbol);
+                    }
+                }
             }
         }
-        part_numbers
+
+        Ok(Adjacency {
+            symbols,
+            numbers,
+            numbers_of_symbol,
+            symbols_of_number,
+        })
     }
 
-    fn get_gear_ratios(&self) -> Vec<u32> {
-        let mut gear_ratios: Vec<u32> = Vec::new();
+    /// The schematic with the counted part numbers, the ignored numbers, the symbols
+    /// and the gears marked, to see what the assignments are based on.
+    pub fn render(&self, style: Style) -> Result<String, Overflow> {
+        let adjacency = self.adjacency()?;
+        let gears: HashSet<(usize, usize)> = adjacency
+            .gears(GEAR_SYMBOL, GEAR_PARTS)
+            .iter()
+            .map(|gear| gear.position)
+            .collect();
 
+        // the mark and length of everything starting at a position
+        let mut marks: HashMap<(usize, usize), (Mark, usize)> = HashMap::new();
+        for (index, number) in adjacency.numbers.iter().enumerate() {
+            let mark = if adjacency.symbols_of(index).is_empty() {
+                Mark::Ignored
+            } else {
+                Mark::Part
+            };
+            let first = number.cells[0];
+            marks.insert((first.row, first.col), (mark, number.cells.len()));
+        }
+        for symbol in &adjacency.symbols {
+            let position = (symbol.row, symbol.col);
+            let mark = if gears.contains(&position) {
+                Mark::Gear
+            } else {
+                Mark::Symbol
+            };
+            marks.insert(position, (mark, 1));
+        }
+
+        let mut text = String::new();
         for row in 0..self.get_row_size() {
-            for col in 0..self.get_col_size() {
-                if let Some(cell) = self.get(row, col) {
-                    if self.is_adjacent_to_number(cell.row, cell.col, '*') {
-                        let adjacent_numbers = self.get_adjacent_numbers(cell.row, cell.col);
-                        let mut gear_ratio = 0;
-
-                        // it is only a valid gear if there are 2 numbers
-                        if adjacent_numbers.len() >= 2 {
-                            for entry in adjacent_numbers {
-                                if gear_ratio == 0 {
-                                    gear_ratio = entry;
-                                } else {
-                                    gear_ratio *= entry;
-                                }
-                            }
-                        }
-                        gear_ratios.push(gear_ratio);
+            let mut col = 0;
+            while let Some(cell) = self.get(row, col) {
+                match marks.get(&(row, col)) {
+                    Some((mark, len)) => {
+                        let marked: String = self.rows[row][col..col + len].iter().collect();
+                        text.push_str(&mark.paint(&marked, style));
+                        col += len;
+                    }
+                    None => {
+                        text.push(cell.value);
+                        col += 1;
                     }
                 }
             }
+            text.push('\n');
         }
-        gear_ratios
+        Ok(text)
+    }
+
+    /// The numbers touching at least one symbol, in the order they appear.
+    pub fn get_part_numbers(&self) -> Result<Vec<u32>, Overflow> {
+        Ok(self.adjacency()?.part_numbers())
+    }
+
+    /// Every `symbol` which touches exactly `parts` distinct numbers. A number
+    /// counts once, no matter how many of its digits touch the symbol.
+    pub fn get_gears(&self, symbol: char, parts: usize) -> Result<Vec<Gear>, Overflow> {
+        Ok(self.adjacency()?.gears(symbol, parts))
     }
 }
 
-fn assignment01(input: &str) -> u32 {
-    let grid = Grid::build_from_input(&input);
-    let part_numbers = grid.get_part_numbers();
+pub fn assignment01(input: &str) -> Solution {
+    let grid = Grid::parse(input, GridOptions::default())?;
+    let part_numbers = grid.get_part_numbers()?;
 
-    let mut sum = 0;
+    let mut sum: u32 = 0;
     for part in part_numbers {
-        sum += part;
+        sum = sum.checked_add(part).ok_or(Overflow)?;
     }
 
-    sum
+    Ok(sum.into())
 }
 
-fn assignment02(input: &str) -> u32 {
-    let grid = Grid::build_from_input(&input);
-    let gear_ratios = grid.get_gear_ratios();
+pub fn assignment02(input: &str) -> Solution {
+    let grid = Grid::parse(input, GridOptions::default())?;
+    let gears = grid.get_gears(GEAR_SYMBOL, GEAR_PARTS)?;
 
-    let mut sum = 0;
-    for ratio in gear_ratios {
-        sum += ratio;
+    let mut sum: u64 = 0;
+    for gear in gears {
+        let ratio = gear.ratio().ok_or(Overflow)?;
+        sum = sum.checked_add(ratio).ok_or(Overflow)?;
     }
 
-    sum
+    Ok(sum.into())
 }
 
-pub fn day03() {
-    let input = aoc_2023::read_input("input/day03.txt").expect("Failed to read file");
+pub fn day03(input_file: &str, visualize: bool) {
+    let input = crate::read_input(input_file).expect("Failed to read file");
 
-    let result_01 = assignment01(&input);
-    let result_02 = assignment02(&input);
+    // if the schematic can't be shown, the assignments report why
+    if visualize {
+        if let Ok(grid) = Grid::parse(&input, GridOptions::default()) {
+            if let Ok(text) = grid.render(Style::detect()) {
+                print!("{text}");
+            }
+        }
+    }
 
-    println!("Day 03 - Assignment 01 solution: {result_01}");
-    println!("Day 03 - Assignment 02 solution: {result_02}");
+    answer::print_solution(3, 1, &assignment01(&input));
+    answer::print_solution(3, 2, &assignment02(&input));
     println!("\n");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answer::Answer;
+    use crate::{generate, property};
+
+    /// Every number of the schematic with its row and its first and last column.
+    fn naive_numbers(rows: &[&[u8]]) -> Vec<(u32, usize, usize, usize)> {
+        let mut numbers = vec![];
+        for (row, line) in rows.iter().enumerate() {
+            let mut col = 0;
+            while col < line.len() {
+                let start = col;
+                while col < line.len() && line[col].is_ascii_digit() {
+                    col += 1;
+                }
+                if col > start {
+                    let value = std::str::from_utf8(&line[start..col]).unwrap();
+                    numbers.push((value.parse().unwrap(), row, start, col - 1));
+                } else {
+                    col += 1;
+                }
+            }
+        }
+        numbers
+    }
+
+    fn touches(number: &(u32, usize, usize, usize), row: usize, col: usize) -> bool {
+        let (_, number_row, first, last) = *number;
+        number_row.abs_diff(row) <= 1 && first <= col + 1 && col <= last + 1
+    }
+
+    #[test]
+    fn parse_test() {
+        let grid = Grid::parse("467..\r\n...*.\r\n  \n\n", GridOptions::default()).unwrap();
+        assert_eq!((grid.get_row_size(), grid.get_col_size()), (2, 5));
+        assert_eq!(grid.get_part_numbers().unwrap(), vec![467]);
+
+        let error = Grid::parse("467..\n...*\n.35..\n", GridOptions::default()).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.message, "Expected a row of 5 cells, found 4");
+
+        let untrimmed = GridOptions {
+            trim: false,
+            ..GridOptions::default()
+        };
+        let error = Grid::parse("467\n...\n   \n\n", untrimmed).unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+
+        let padded = GridOptions {
+            ragged_rows: RaggedRows::Pad,
+            ..GridOptions::default()
+        };
+        let grid = Grid::parse("467\n..*..\n.35\n", padded).unwrap();
+        assert_eq!((grid.get_row_size(), grid.get_col_size()), (3, 5));
+        assert_eq!(grid.get(0, 4).map(|cell| cell.value), Some('.'));
+        assert_eq!(grid.get_gears('*', 2).unwrap()[0].parts, vec![467, 35]);
+    }
+
+    #[test]
+    fn render_test() {
+        let grid = Grid::parse(
+            "467.#\n..*..\n.35..\n...$.\n9....\n",
+            GridOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            grid.render(Style::Plain).unwrap(),
+            "[467].<#>\n..{*}..\n.[35]..\n...<$>.\n(9)....\n"
+        );
+        assert_eq!(
+            grid.render(Style::Ansi).unwrap().lines().nth(1),
+            Some("..\x1b[1;33m*\x1b[0m..")
+        );
+    }
 
-    const EXAMPLE_DATA: &'static str = r"467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..
-        ";
+    #[test]
+    fn non_square_test() {
+        // numbers beyond the number of rows used to be cut off from their symbols
+        let grid = Grid::parse("......12\n.......*\n", GridOptions::default()).unwrap();
+        assert_eq!(grid.get_part_numbers().unwrap(), vec![12]);
+        assert_eq!(grid.adjacency().unwrap().numbers_of(0), &[0]);
+    }
+
+    #[test]
+    fn overflow_test() {
+        let input = "99999999999*\n..........*1\n";
+        let grid = Grid::parse(input, GridOptions::default()).unwrap();
+        assert_eq!(grid.get_part_numbers(), Err(Overflow));
+        assert_eq!(grid.get_gears('*', 1), Err(Overflow));
+        assert!(grid.adjacency().is_err());
+        assert_eq!(assignment01(input), Err(Overflow.into()));
+        assert_eq!(assignment02(input), Err(Overflow.into()));
+    }
 
     #[test]
-    fn assignment01_test() {
-        assert_eq!(assignment01(&EXAMPLE_DATA.to_owned()), 4361);
+    fn gears_test() {
+        let grid = Grid::parse(
+            "467..\n..*..\n.35.7\n...*.\n..12.\n",
+            GridOptions::default(),
+        )
+        .unwrap();
+        // the three digits of 467 only count once, the second star touches three numbers
+        assert_eq!(
+            grid.get_gears('*', 2).unwrap(),
+            vec![Gear {
+                position: (1, 2),
+                parts: vec![467, 35],
+            }]
+        );
+        assert_eq!(grid.get_gears('*', 2).unwrap()[0].ratio(), Some(16345));
+
+        let grid = Grid::parse("1.2\n.*.\n3..\n", GridOptions::default()).unwrap();
+        assert_eq!(grid.get_gears('*', 2).unwrap(), vec![]);
+        assert_eq!(grid.get_gears('*', 3).unwrap()[0].parts, vec![1, 2, 3]);
+        assert_eq!(grid.get_gears('#', 3).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn adjacency_test() {
+        let grid = Grid::parse(
+            "467.#\n..*..\n.35.7\n...*.\n..12.\n",
+            GridOptions::default(),
+        )
+        .unwrap();
+        let adjacency = grid.adjacency().unwrap();
+        let values = |numbers: &[usize]| -> Vec<u32> {
+            numbers
+                .iter()
+                .map(|number| adjacency.numbers[*number].value)
+                .collect()
+        };
+
+        assert_eq!(adjacency.symbols.len(), 3);
+        assert_eq!(values(adjacency.numbers_of(1)), vec![467, 35]);
+        assert_eq!(values(adjacency.numbers_of(2)), vec![35, 7, 12]);
+        assert_eq!(adjacency.symbols_of(1), &[1, 2]);
+        assert_eq!(adjacency.numbers[0].cells.len(), 3);
+
+        let isolated: Vec<u32> = adjacency
+            .isolated_numbers()
+            .iter()
+            .map(|number| number.value)
+            .collect();
+        assert_eq!(isolated, Vec::<u32>::new());
+        assert_eq!(adjacency.symbols_touching_at_least(3)[0].row, 3);
+        assert_eq!(
+            adjacency.totals_by_symbol(),
+            BTreeMap::from([('*', 467 + 35 + 7 + 12)])
+        );
     }
 
     #[test]
-    fn assignment02_test() {
-        assert_eq!(assignment02(&EXAMPLE_DATA.to_owned()), 467835);
+    fn adjacency_reference_test() {
+        property::check(50, generate::day03, |input| {
+            let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+            let numbers = naive_numbers(&rows);
+            let adjacency = Grid::parse(input, GridOptions::default())
+                .unwrap()
+                .adjacency()
+                .unwrap();
+            assert_eq!(adjacency.numbers.len(), numbers.len());
+
+            for (symbol, cell) in adjacency.symbols.iter().enumerate() {
+                let mut expected: Vec<u32> = numbers
+                    .iter()
+                    .filter(|number| touches(number, cell.row, cell.col))
+                    .map(|number| number.0)
+                    .collect();
+                let mut actual: Vec<u32> = adjacency
+                    .numbers_of(symbol)
+                    .iter()
+                    .map(|number| adjacency.numbers[*number].value)
+                    .collect();
+                expected.sort();
+                actual.sort();
+                assert_eq!(actual, expected, "symbol at {cell:?}");
+            }
+
+            let mut totals: BTreeMap<char, u64> = BTreeMap::new();
+            for kind in adjacency.symbols.iter().map(|cell| cell.value) {
+                let total = numbers
+                    .iter()
+                    .filter(|number| {
+                        adjacency
+                            .symbols
+                            .iter()
+                            .any(|cell| cell.value == kind && touches(number, cell.row, cell.col))
+                    })
+                    .map(|number| number.0 as u64)
+                    .sum();
+                totals.insert(kind, total);
+            }
+            totals.retain(|_, total| *total > 0);
+            assert_eq!(adjacency.totals_by_symbol(), totals);
+
+            let isolated: u64 = adjacency
+                .isolated_numbers()
+                .iter()
+                .map(|number| number.value as u64)
+                .sum();
+            let all: u64 = numbers.iter().map(|number| number.0 as u64).sum();
+            assert_eq!(Ok(Answer::from(all - isolated)), assignment01(input));
+        });
+    }
+
+    #[test]
+    fn reference_test() {
+        property::check(50, generate::day03, |input| {
+            let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+            let numbers = naive_numbers(&rows);
+            let symbols: Vec<(u8, usize, usize)> = rows
+                .iter()
+                .enumerate()
+                .flat_map(|(row, line)| line.iter().enumerate().map(move |(col, c)| (*c, row, col)))
+                .filter(|(c, _, _)| *c != b'.' && !c.is_ascii_digit())
+                .collect();
+
+            let part_sum: u32 = numbers
+                .iter()
+                .filter(|number| {
+                    symbols
+                        .iter()
+                        .any(|(_, row, col)| touches(number, *row, *col))
+                })
+                .map(|number| number.0)
+                .sum();
+            assert_eq!(assignment01(input), Ok(Answer::from(part_sum)));
+
+            let mut gear_sum: u64 = 0;
+            for (_, row, col) in symbols.iter().filter(|(c, _, _)| *c == b'*') {
+                let parts: Vec<u64> = numbers
+                    .iter()
+                    .filter(|number| touches(number, *row, *col))
+                    .map(|number| number.0 as u64)
+                    .collect();
+                if parts.len() == 2 {
+                    gear_sum += parts[0] * parts[1];
+                }
+            }
+            assert_eq!(assignment02(input), Ok(Answer::from(gear_sum)));
+        });
     }
 }
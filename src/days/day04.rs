@@ -1,14 +1,20 @@
 use std::collections::HashMap;
 
+use crate::answer::{self, Overflow, Solution};
+use crate::parser::{
+    self, block, delimited, labelled_line, number, pair, preceded, separated_list, space1, tag,
+    terminated, ParseError, Parser,
+};
+
 #[derive(Eq, PartialEq, Hash, Clone)]
-struct Card {
-    id: usize,
-    winning_numbers: Vec<u32>,
-    drawn_numbers: Vec<u32>,
+pub struct Card {
+    pub id: usize,
+    pub winning_numbers: Vec<u32>,
+    pub drawn_numbers: Vec<u32>,
 }
 
 impl Card {
-    fn new(id: usize, winning_numbers: Vec<u32>, drawn_numbers: Vec<u32>) -> Card {
+    pub fn new(id: usize, winning_numbers: Vec<u32>, drawn_numbers: Vec<u32>) -> Card {
         Card {
             id,
             winning_numbers,
@@ -16,28 +22,27 @@ impl Card {
         }
     }
 
-    fn get_card_score(&self) -> u32 {
-        let mut score = 0;
+    /// Returns `None` if the score doesn't fit into an u32.
+    pub fn get_card_score(&self) -> Option<u32> {
+        let mut score: u32 = 0;
 
         for number in &self.winning_numbers {
-            if self.drawn_numbers.contains(&number) {
+            if self.drawn_numbers.contains(number) {
                 if score == 0 {
                     score = 1;
-                } else if score == 1 {
-                    score += score;
                 } else {
-                    score *= 2;
+                    score = score.checked_mul(2)?;
                 }
             }
         }
-        score
+        Some(score)
     }
 
-    fn get_amount_of_winning_numbers(&self) -> usize {
+    pub fn get_amount_of_winning_numbers(&self) -> usize {
         let mut amount = 0;
 
         for number in &self.winning_numbers {
-            if self.drawn_numbers.contains(&number) {
+            if self.drawn_numbers.contains(number) {
                 amount += 1;
             }
         }
@@ -45,35 +50,29 @@ impl Card {
     }
 }
 
-fn parse_input(text: &str) -> Vec<Card> {
-    let mut cards: Vec<Card> = Vec::new();
-    let mut card_id: usize = 0;
-
-    for line in text.lines() {
-        let start_pos = line.find(':').expect("Invalid input");
-        let numbers = &line[start_pos + 1..];
-        let mut number_parts = numbers.split_terminator('|');
-
-        let left_part = number_parts.next().unwrap().trim();
-        let right_part = number_parts.next().unwrap().trim();
-
-        let winning_numbers: Vec<u32> = left_part
-            .split_whitespace()
-            .map(|s| s.parse::<u32>().expect("Failed to parse number"))
-            .collect();
-        let drawn_numbers: Vec<u32> = right_part
-            .split_whitespace()
-            .map(|s| s.parse::<u32>().expect("Failed to parse number"))
-            .collect();
-
-        cards.push(Card::new(card_id, winning_numbers, drawn_numbers));
-        card_id += 1;
-    }
+/// `Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53`
+fn card_line<'a>() -> impl Parser<'a, (usize, (Vec<u32>, Vec<u32>))> {
+    let id = preceded(pair(tag("Card"), space1()), number::<usize>());
+    let winning_numbers = separated_list(space1(), number::<u32>());
+    let drawn_numbers = separated_list(space1(), number::<u32>());
+    let separator = delimited(space1(), tag("|"), space1());
+    labelled_line(
+        id,
+        pair(terminated(winning_numbers, separator), drawn_numbers),
+    )
+}
+
+pub(crate) fn parse_input(text: &str) -> Result<Vec<Card>, ParseError> {
+    let lines = parser::parse_all(block(card_line()), text)?;
 
-    cards
+    let cards = lines
+        .into_iter()
+        .map(|(id, (winning_numbers, drawn_numbers))| Card::new(id, winning_numbers, drawn_numbers))
+        .collect();
+    Ok(cards)
 }
 
-fn calculate_total_cards(cards: Vec<Card>) -> HashMap<usize, usize> {
+pub(crate) fn calculate_total_cards(cards: Vec<Card>) -> HashMap<usize, usize> {
     let mut initial_cards: HashMap<usize, usize> =
         cards.clone().into_iter().map(|obj| (obj.id, 1)).collect();
 
@@ -94,57 +93,93 @@ fn calculate_total_cards(cards: Vec<Card>) -> HashMap<usize, usize> {
     initial_cards
 }
 
-fn assignment01(input: &str) -> u32 {
-    let cards = parse_input(input);
+pub fn assignment01(input: &str) -> Solution {
+    let cards = parse_input(input)?;
 
-    let mut total_card_score = 0;
+    let mut total_card_score: u32 = 0;
     for card in cards {
-        total_card_score += card.get_card_score();
+        let score = card.get_card_score().ok_or(Overflow)?;
+        total_card_score = total_card_score.checked_add(score).ok_or(Overflow)?;
     }
-    total_card_score
+    Ok(total_card_score.into())
 }
 
-fn assignment02(input: &str) -> u32 {
-    let cards = parse_input(input);
+pub fn assignment02(input: &str) -> Solution {
+    let cards = parse_input(input)?;
 
     let result = calculate_total_cards(cards);
-    let mut sum = 0;
+    let mut sum: usize = 0;
     for (_, amount) in result {
-        sum += amount;
+        sum = sum.checked_add(amount).ok_or(Overflow)?;
     }
-    sum as u32
+    Ok(sum.into())
 }
 
-pub fn day04() {
-    let input = aoc_2023::read_input("input/day04.txt").expect("Failed to read file");
+pub fn day04(input_file: &str) {
+    let input = crate::read_input(input_file).expect("Failed to read file");
 
-    let result_01 = assignment01(&input);
-    let result_02 = assignment02(&input);
-
-    println!("Day 04 - Assignment 01 solution: {result_01}");
-    println!("Day 04 - Assignment 02 solution: {result_02}");
+    answer::print_solution(4, 1, &assignment01(&input));
+    answer::print_solution(4, 2, &assignment02(&input));
     println!("\n");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const EXAMPLE_DATA: &'static str = r"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-";
+    use crate::answer::Answer;
+    use crate::{generate, property};
+
+    /// Splits a card line into its id, winning numbers and drawn numbers.
+    fn naive_card(line: &str) -> (usize, Vec<u32>, Vec<u32>) {
+        let (id, numbers) = line.strip_prefix("Card").unwrap().split_once(':').unwrap();
+        let (winning, drawn) = numbers.split_once('|').unwrap();
+        let parse = |numbers: &str| {
+            numbers
+                .split_whitespace()
+                .map(|number| number.parse().unwrap())
+                .collect()
+        };
+        (id.trim().parse().unwrap(), parse(winning), parse(drawn))
+    }
 
     #[test]
-    fn assignment01_test() {
-        assert_eq!(assignment01(&EXAMPLE_DATA.to_owned()), 13);
+    fn round_trip_test() {
+        property::check(100, generate::day04, |input| {
+            let cards = parse_input(input).unwrap();
+            let expected: Vec<(usize, Vec<u32>, Vec<u32>)> =
+                input.lines().map(naive_card).collect();
+            let actual: Vec<(usize, Vec<u32>, Vec<u32>)> = cards
+                .into_iter()
+                .map(|card| (card.id, card.winning_numbers, card.drawn_numbers))
+                .collect();
+            assert_eq!(actual, expected);
+        });
     }
 
     #[test]
-    fn assignment02_test() {
-        assert_eq!(assignment02(&EXAMPLE_DATA.to_owned()), 30);
+    fn reference_test() {
+        property::check(100, generate::day04, |input| {
+            let cards: Vec<(usize, Vec<u32>, Vec<u32>)> = input.lines().map(naive_card).collect();
+            let matches: Vec<usize> = cards
+                .iter()
+                .map(|(_, winning, drawn)| drawn.iter().filter(|n| winning.contains(n)).count())
+                .collect();
+
+            let score: u32 = matches
+                .iter()
+                .filter(|matches| **matches > 0)
+                .map(|matches| 1 << (matches - 1))
+                .sum();
+            assert_eq!(assignment01(input), Ok(Answer::from(score)));
+
+            // process every single copy of every card
+            let mut pile: Vec<usize> = (0..cards.len()).collect();
+            let mut total = 0;
+            while let Some(card) = pile.pop() {
+                total += 1;
+                pile.extend(card + 1..(card + 1 + matches[card]).min(cards.len()));
+            }
+            assert_eq!(assignment02(input), Ok(Answer::from(total)));
+        });
     }
 }
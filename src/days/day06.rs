@@ -1,123 +1,429 @@
-fn extract_multiple_races(input: &str) -> (Vec<u32>, Vec<u32>) {
-    let lines: Vec<&str> = input.lines().collect();
+use crate::answer::{self, Answer, BigInt, Overflow, Solution};
+use crate::math;
+use crate::parser::{self, numeric_line, pair, position, ParseError};
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
 
-    let duration: Vec<u32> = lines[0]
-        .split_whitespace()
-        .filter_map(|s| s.parse::<u32>().ok())
-        .collect();
-
-    let distance: Vec<u32> = lines[1]
-        .split_whitespace()
-        .filter_map(|s| s.parse::<u32>().ok())
-        .collect();
-
-    assert_eq!(duration.len(), distance.len());
-
-    (duration, distance)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    pub record: u64,
 }
 
-fn calculate_win_possibilities_per_race(input: &str) -> Vec<u32> {
-    let (duration, distance) = extract_multiple_races(input);
+/// The races of the sheet, one per column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RaceSheet {
+    pub races: Vec<Race>,
+}
 
-    let mut possible_wins_per_race: Vec<u32> = vec![];
+impl FromStr for RaceSheet {
+    type Err = ParseError;
 
-    for i in 0..duration.len() {
-        let duration = duration[i];
-        let distance = distance[i];
-        let mut possible_wins_for_race = 0;
-        for j in 0..duration {
-            let remaining_duration = duration - j;
-            let distance_traveled = j * remaining_duration;
+    /// `Time:      7  15   30` followed by `Distance:  9  40  200`
+    fn from_str(input: &str) -> Result<RaceSheet, ParseError> {
+        let sheet = pair(
+            numeric_line::<u64>(Some("Time")),
+            pair(position(), numeric_line::<u64>(Some("Distance"))),
+        );
+        let (times, (position, records)) = parser::parse_all(sheet, input)?;
 
-            if distance_traveled > distance {
-                possible_wins_for_race += 1;
-            }
+        if times.len() != records.len() {
+            let message = format!(
+                "Expected {} distances for {} times, found {}",
+                times.len(),
+                times.len(),
+                records.len()
+            );
+            return Err(position.error(message));
         }
-        possible_wins_per_race.push(possible_wins_for_race);
+        let races = times
+            .into_iter()
+            .zip(records)
+            .map(|(time, record)| Race { time, record })
+            .collect();
+        Ok(RaceSheet { races })
+    }
+}
+
+impl RaceSheet {
+    /// All columns read as a single race, as if the spaces between them were
+    /// bad kerning: `7  15   30` becomes `71530`.
+    pub fn kerned(&self) -> Result<Race, Overflow> {
+        let time = join_digits(self.races.iter().map(|race| race.time)).ok_or(Overflow)?;
+        let record = join_digits(self.races.iter().map(|race| race.record)).ok_or(Overflow)?;
+        Ok(Race { time, record })
     }
 
-    possible_wins_per_race
+    /// The holds with which the boat wins every race of the sheet. Every window
+    /// is contiguous, so the holds which win all of them are too.
+    pub fn common_holds(&self, boat: Boat) -> RangeInclusive<u64> {
+        self.races
+            .iter()
+            .map(|race| boat.winning_window(*race))
+            .reduce(|common, holds| {
+                *common.start().max(holds.start())..=*common.end().min(holds.end())
+            })
+            .unwrap_or(NO_HOLDS)
+    }
 }
 
-fn extract_single_race(input: &str) -> (u64, u64) {
-    let lines: Vec<&str> = input.lines().collect();
+/// Appends the decimal digits of the numbers to each other.
+fn join_digits(mut numbers: impl Iterator<Item = u64>) -> Option<u64> {
+    numbers.try_fold(0u64, |joined, number| {
+        // leading zeros don't change the value, and shifting them could overflow
+        if joined == 0 {
+            return Some(number);
+        }
+        let digits = number.checked_ilog10().unwrap_or(0) + 1;
+        joined
+            .checked_mul(10u64.checked_pow(digits)?)?
+            .checked_add(number)
+    })
+}
 
-    let duration_str: String = lines[0]
-        .split_whitespace()
-        .filter_map(|s| s.parse::<u64>().ok())
-        .map(|s| s.to_string())
-        .collect();
+/// The hold times which travel further than the record. Holding for `h` ms covers
+/// `h * (duration - h)` mm, so the window lies symmetrically around `duration / 2`
+/// between the roots of `h^2 - duration * h + record`.
+pub fn winning_window(duration: u64, record: u64) -> Range<u64> {
+    let (duration, record) = (duration as u128, record as u128);
+    let beats = |hold: u128| hold <= duration && hold * (duration - hold) > record;
 
-    let distance_str: String = lines[1]
-        .split_whitespace()
-        .filter_map(|s| s.parse::<u64>().ok())
-        .map(|s| s.to_string())
-        .collect();
+    let discriminant = (duration * duration).saturating_sub(4 * record);
+    // the root rounded to an integer, off by at most one in either direction
+    let mut low = (duration - math::isqrt_u128(discriminant)) / 2;
+    while low > 0 && beats(low - 1) {
+        low -= 1;
+    }
+    while low <= duration / 2 && !beats(low) {
+        low += 1;
+    }
+    if low > duration / 2 {
+        return 0..0;
+    }
+    low as u64..(duration - low + 1) as u64
+}
 
-    let duration = duration_str.parse::<u64>().expect("Failed to parse value");
-    let distance = distance_str.parse::<u64>().expect("Failed to parse value");
+/// An empty window of holds.
+const NO_HOLDS: RangeInclusive<u64> = RangeInclusive::new(1, 0);
 
-    (duration, distance)
+/// Whether a distance equal to the record counts as a win.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TiePolicy {
+    Lose,
+    Win,
 }
 
-fn calculate_win_possibilities_for_race(input: &str) -> u64 {
-    let (duration, distance) = extract_single_race(input);
+/// How a boat moves: holding the button for `h` ms gives a speed of
+/// `acceleration * h` mm/ms for the rest of the race.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Boat {
+    pub acceleration: u64,
+    /// The longest the button can be held, `None` for the whole race.
+    pub max_charge: Option<u64>,
+    pub ties: TiePolicy,
+}
 
-    let mut possible_wins_per_race: u64 = 0;
+impl Boat {
+    /// The boat of the puzzle, which has to travel strictly further than the record.
+    pub const PUZZLE: Boat = Boat {
+        acceleration: 1,
+        max_charge: None,
+        ties: TiePolicy::Lose,
+    };
 
-    for i in 0..duration {
-        let remaining_duration = duration - i;
-        let distance_traveled = i * remaining_duration;
+    /// The longest possible hold in a race of `time` ms.
+    pub fn max_hold(&self, time: u64) -> u64 {
+        self.max_charge
+            .map_or(time, |max_charge| max_charge.min(time))
+    }
+
+    /// The distance travelled when holding for `hold` ms. A boat which is held
+    /// for the whole race doesn't move. `None` if the distance doesn't fit into an u128.
+    pub fn distance(&self, time: u64, hold: u64) -> Option<u128> {
+        let travel = time.saturating_sub(hold) as u128;
+        (self.acceleration as u128 * hold as u128).checked_mul(travel)
+    }
+
+    /// A hold which goes as far as possible. The distance grows up to half the race,
+    /// so that's the best hold unless the button can't be held that long.
+    pub fn optimal_hold(&self, time: u64) -> u64 {
+        self.max_hold(time).min(time / 2)
+    }
 
-        if distance_traveled > distance {
-            possible_wins_per_race += 1;
+    pub fn beats(&self, distance: u128, record: u64) -> bool {
+        match self.ties {
+            TiePolicy::Lose => distance > record as u128,
+            TiePolicy::Win => distance >= record as u128,
         }
     }
 
-    possible_wins_per_race
+    /// All holds which beat the record of the race.
+    pub fn winning_window(&self, race: Race) -> RangeInclusive<u64> {
+        let last = self.max_hold(race.time);
+        // a distance of `acceleration * x` beats the record if `x` beats a record of
+        // `record / acceleration`, rounded to make up for the tie policy
+        let record = match (self.acceleration, self.ties) {
+            (_, TiePolicy::Win) if race.record == 0 => return 0..=last,
+            (0, _) => return NO_HOLDS,
+            (acceleration, TiePolicy::Lose) => race.record / acceleration,
+            (acceleration, TiePolicy::Win) => race.record.div_ceil(acceleration) - 1,
+        };
+        let window = winning_window(race.time, record);
+        if window.is_empty() {
+            return NO_HOLDS;
+        }
+        window.start..=(window.end - 1).min(last)
+    }
 }
 
-fn assignment01(input: &str) -> u32 {
-    let possible_wins_per_race = calculate_win_possibilities_per_race(input);
-
-    let mut sum = 0;
-    for race in possible_wins_per_race {
-        sum = if sum == 0 { race } else { sum * race }
+fn win_possibilities(race: Race) -> u64 {
+    let window = Boat::PUZZLE.winning_window(race);
+    if window.is_empty() {
+        return 0;
     }
+    // a hold of 0 never beats a record, so the count fits
+    window.end() - window.start() + 1
+}
+
+pub fn assignment01(input: &str) -> Solution {
+    let sheet = RaceSheet::from_str(input)?;
 
-    sum
+    // the product of many races can grow beyond any fixed size integer
+    let product: BigInt = sheet
+        .races
+        .into_iter()
+        .map(|race| BigInt::from(win_possibilities(race)))
+        .product();
+
+    Ok(Answer::Integer(product))
 }
 
-fn assignment02(input: &str) -> u64 {
-    calculate_win_possibilities_for_race(input)
+pub fn assignment02(input: &str) -> Solution {
+    let sheet = RaceSheet::from_str(input)?;
+
+    Ok(win_possibilities(sheet.kerned()?).into())
 }
 
-pub fn day06() {
-    let input = aoc_2023::read_input("input/day06.txt").expect("Failed to read file");
+pub fn day06(input_file: &str) {
+    let input = crate::read_input(input_file).expect("Failed to read file");
 
-    let result_01 = assignment01(&input);
-    let result_02 = assignment02(&input);
+    answer::print_solution(6, 1, &assignment01(&input));
+    answer::print_solution(6, 2, &assignment02(&input));
 
-    println!("Day 06 - Assignment 01 solution: {result_01}");
-    println!("Day 06 - Assignment 02 solution: {result_02}");
+    if let Ok(sheet) = RaceSheet::from_str(&input) {
+        let holds = sheet.common_holds(Boat::PUZZLE);
+        if !holds.is_empty() {
+            println!("Day 06 - Holds which win every race: {holds:?}");
+        }
+    }
     println!("\n");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answer::Answer;
+    use crate::{generate, property};
 
-    const EXAMPLE_DATA: &'static str = r"Time:      7  15   30
-Distance:  9  40  200
-";
+    /// The numbers after the label of both lines.
+    fn naive_columns(input: &str) -> (Vec<&str>, Vec<&str>) {
+        let mut lines = input
+            .lines()
+            .map(|line| line.split_whitespace().skip(1).collect());
+        (lines.next().unwrap(), lines.next().unwrap())
+    }
+
+    fn naive_wins(time: u64, record: u64) -> u64 {
+        (0..=time)
+            .filter(|hold| hold * (time - hold) > record)
+            .count() as u64
+    }
+
+    #[test]
+    fn winning_window_test() {
+        assert_eq!(winning_window(7, 9), 2..6);
+        assert_eq!(winning_window(30, 200), 11..20);
+        // exactly the record isn't enough
+        assert_eq!(winning_window(4, 4), 0..0);
+        assert_eq!(winning_window(4, 3), 2..3);
+        assert_eq!(winning_window(0, 0), 0..0);
+        assert_eq!(winning_window(u64::MAX, 0), 1..u64::MAX);
+
+        for time in 0..60 {
+            for record in 0..(time * time / 4 + 2) {
+                let window = winning_window(time, record);
+                let holds: Vec<u64> = (0..=time)
+                    .filter(|hold| hold * (time - hold) > record)
+                    .collect();
+                assert_eq!(window.collect::<Vec<u64>>(), holds, "{time} {record}");
+            }
+        }
+    }
+
+    #[test]
+    fn boat_test() {
+        let race = Race {
+            time: 30,
+            record: 200,
+        };
+        assert_eq!(Boat::PUZZLE.winning_window(race), 11..=19);
+        assert_eq!(Boat::PUZZLE.optimal_hold(30), 15);
+        assert_eq!(Boat::PUZZLE.distance(30, 10), Some(200));
+
+        let boat = Boat {
+            max_charge: Some(12),
+            ties: TiePolicy::Win,
+            ..Boat::PUZZLE
+        };
+        assert_eq!(boat.winning_window(race), 10..=12);
+        assert_eq!(boat.optimal_hold(30), 12);
+
+        let fast = Boat {
+            acceleration: u64::MAX,
+            ..Boat::PUZZLE
+        };
+        assert_eq!(fast.distance(u64::MAX, u64::MAX / 2), None);
+
+        // Time: 18446744073709551615 / Distance: 0
+        let race = Race {
+            time: u64::MAX,
+            record: 0,
+        };
+        assert_eq!(Boat::PUZZLE.winning_window(race), 1..=u64::MAX - 1);
+        let tie = Boat {
+            ties: TiePolicy::Win,
+            ..Boat::PUZZLE
+        };
+        assert_eq!(tie.winning_window(race), 0..=u64::MAX);
+        assert_eq!(
+            assignment02("Time: 18446744073709551615\nDistance: 0\n"),
+            Ok(Answer::from(u64::MAX - 1))
+        );
+
+        // every boat against a search over all holds
+        for acceleration in 0..4 {
+            for max_charge in [None, Some(0), Some(3), Some(8)] {
+                for ties in [TiePolicy::Lose, TiePolicy::Win] {
+                    let boat = Boat {
+                        acceleration,
+                        max_charge,
+                        ties,
+                    };
+                    for time in 0..16 {
+                        let holds = 0..=boat.max_hold(time);
+                        let best = holds.clone().map(|hold| boat.distance(time, hold)).max();
+                        assert_eq!(Some(boat.distance(time, boat.optimal_hold(time))), best);
+
+                        for record in 0..(acceleration * time * time / 4 + 2) {
+                            let race = Race { time, record };
+                            let expected: Vec<u64> = holds
+                                .clone()
+                                .filter(|hold| {
+                                    boat.beats(boat.distance(time, *hold).unwrap(), record)
+                                })
+                                .collect();
+                            let window = boat.winning_window(race);
+                            assert_eq!(window.collect::<Vec<u64>>(), expected, "{boat:?} {race:?}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Part 2 joins all columns, so only a few races are generated.
+    fn races(rng: &mut crate::random::Rng, size: usize) -> String {
+        generate::day06(rng, size.min(3))
+    }
 
     #[test]
-    fn assignment01_test() {
-        assert_eq!(assignment01(&EXAMPLE_DATA.to_owned()), 288);
+    fn round_trip_test() {
+        property::check(100, races, |input| {
+            let (times, records) = naive_columns(input);
+            let races: Vec<Race> = times
+                .iter()
+                .zip(&records)
+                .map(|(time, record)| Race {
+                    time: time.parse().unwrap(),
+                    record: record.parse().unwrap(),
+                })
+                .collect();
+            assert_eq!(RaceSheet::from_str(input), Ok(RaceSheet { races }));
+        });
     }
 
     #[test]
-    fn assignment02_test() {
-        assert_eq!(assignment02(&EXAMPLE_DATA.to_owned()), 71503);
+    #[allow(clippy::single_range_in_vec_init)]
+    fn race_sheet_test() {
+        let sheet = RaceSheet::from_str("Time:  7 15  30\nDistance: 9 40 200\n").unwrap();
+        assert_eq!(
+            sheet.races[2],
+            Race {
+                time: 30,
+                record: 200
+            }
+        );
+        assert_eq!(
+            sheet.kerned(),
+            Ok(Race {
+                time: 71530,
+                record: 940200
+            })
+        );
+
+        // zeros still take a column
+        let sheet = RaceSheet::from_str("Time: 10 0 7\nDistance: 0 100 5\n").unwrap();
+        assert_eq!(
+            sheet.kerned(),
+            Ok(Race {
+                time: 1007,
+                record: 1005
+            })
+        );
+
+        let sheet = RaceSheet::from_str("Time: 1844674407 3709551616\nDistance: 1 2").unwrap();
+        assert_eq!(sheet.kerned(), Err(Overflow));
+
+        // a single column needs no room for further digits
+        let sheet = RaceSheet::from_str("Time: 18446744073709551615\nDistance: 0").unwrap();
+        assert_eq!(
+            sheet.kerned(),
+            Ok(Race {
+                time: u64::MAX,
+                record: 0
+            })
+        );
+        let sheet = RaceSheet::from_str("Time: 0 18446744073709551615\nDistance: 0 7").unwrap();
+        assert_eq!(sheet.kerned().map(|race| race.time), Ok(u64::MAX));
+
+        // 2..=5 and 3..=5 win the races
+        let sheet = RaceSheet::from_str("Time: 7 8\nDistance: 9 12\n").unwrap();
+        assert_eq!(sheet.common_holds(Boat::PUZZLE), 3..=5);
+        let sheet = RaceSheet::from_str("Time: 7 15 30\nDistance: 9 40 200\n").unwrap();
+        assert!(sheet.common_holds(Boat::PUZZLE).is_empty());
+
+        let error = RaceSheet::from_str("Time: 7 15 30\nDistance: 9 40\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.message, "Expected 3 distances for 3 times, found 2");
+    }
+
+    #[test]
+    fn reference_test() {
+        property::check(50, races, |input| {
+            let (times, records) = naive_columns(input);
+            let product: u64 = times
+                .iter()
+                .zip(&records)
+                .map(|(time, record)| naive_wins(time.parse().unwrap(), record.parse().unwrap()))
+                .product();
+            assert_eq!(assignment01(input), Ok(Answer::from(product)));
+
+            let time = times.concat().parse().unwrap();
+            let record = records.concat().parse().unwrap();
+            assert_eq!(
+                assignment02(input),
+                Ok(Answer::from(naive_wins(time, record)))
+            );
+        });
     }
 }
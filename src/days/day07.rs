@@ -1,8 +1,18 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
-#[derive(Eq, PartialEq, PartialOrd, Debug)]
-enum HandType {
+use crate::answer::{self, Overflow, Solution};
+use crate::parser::{
+    self, block, line, number, pair, preceded, space1, take_while1, Input, ParseError, Parser,
+};
+
+/// The strength of the cards, from the best to the worst card.
+pub const ORDERING: &str = "AKQJT98765432";
+/// The strength of the cards when `J` is a joker, which is the weakest card on its own.
+pub const JOKER_ORDERING: &str = "AKQT98765432J";
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum HandType {
     FiveOfAKind,
     FourOfAKind,
     FullHouse,
@@ -48,24 +58,42 @@ impl Ord for HandType {
     }
 }
 
-#[derive(Debug, Eq, PartialOrd)]
-struct Hand {
+impl PartialOrd for HandType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Eq)]
+pub struct Hand {
     cards: Vec<char>,
     bid: usize,
     r#type: HandType,
 }
 
 impl Hand {
-    fn new(cards: Vec<char>, bid: usize) -> Self {
+    pub fn new(cards: Vec<char>, bid: usize) -> Self {
         let r#type = Self::determine_type(&cards);
         Hand { cards, bid, r#type }
     }
 
-    fn new_with_joker(cards: Vec<char>, bid: usize) -> Self {
+    pub fn new_with_joker(cards: Vec<char>, bid: usize) -> Self {
         let r#type = Self::determine_type_with_joker(&cards);
         Hand { cards, bid, r#type }
     }
 
+    pub fn cards(&self) -> &[char] {
+        &self.cards
+    }
+
+    pub fn bid(&self) -> usize {
+        self.bid
+    }
+
+    pub fn hand_type(&self) -> HandType {
+        self.r#type
+    }
+
     #[inline]
     fn calculate_type(card_types: HashMap<char, usize>) -> HandType {
         match card_types.len() {
@@ -139,7 +167,7 @@ impl Hand {
 
     /// I'm using a custom comparison function instead of implementing Ord.
     /// Mainly to pass in a custom set of ordering rules depending on the assignment.
-    fn cmp(&self, other: &Self, ordering: &str) -> Ordering {
+    pub fn compare_with(&self, other: &Self, ordering: &str) -> Ordering {
         match self.r#type.cmp(&other.r#type) {
             Ordering::Equal => {
                 // If the hand has the same type, the hand
@@ -164,105 +192,175 @@ impl PartialEq for Hand {
     }
 }
 
-fn extract_input_01(input: &str) -> Vec<Hand> {
-    let mut hands: Vec<Hand> = vec![];
-
-    for line in input.lines() {
-        let mut entry = line.split_whitespace();
-
-        let cards: Vec<char> = entry.next().unwrap().chars().collect();
-        let bid = entry
-            .next()
-            .unwrap()
-            .parse::<usize>()
-            .expect("Failed to parse value");
-
-        hands.push(Hand::new(cards, bid));
+/// Exactly five cards out of `AKQJT98765432`.
+fn cards<'a>() -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        let (next, cards) = take_while1(|c| ORDERING.contains(c), "a card").parse(input)?;
+        match next.rest().chars().next() {
+            Some(c) if !c.is_whitespace() => Err(next.error(format!("Unknown card '{c}'"))),
+            _ if cards.len() != 5 => {
+                Err(input.error(format!("Expected 5 cards, found {}", cards.len())))
+            }
+            _ => Ok((next, cards)),
+        }
     }
-
-    hands
 }
 
-fn extract_input_02(input: &str) -> Vec<Hand> {
-    let mut hands: Vec<Hand> = vec![];
+/// `32T3K 765`
+fn hand_line<'a>() -> impl Parser<'a, (&'a str, usize)> {
+    line(pair(cards(), preceded(space1(), number::<usize>())))
+}
 
-    for line in input.lines() {
-        let mut entry = line.split_whitespace();
+fn extract_input(
+    input: &str,
+    new_hand: fn(Vec<char>, usize) -> Hand,
+) -> Result<Vec<Hand>, ParseError> {
+    let lines = parser::parse_all(block(hand_line()), input)?;
 
-        let cards: Vec<char> = entry.next().unwrap().chars().collect();
-        let bid = entry
-            .next()
-            .unwrap()
-            .parse::<usize>()
-            .expect("Failed to parse value");
+    Ok(lines
+        .into_iter()
+        .map(|(cards, bid)| new_hand(cards.chars().collect(), bid))
+        .collect())
+}
 
-        hands.push(Hand::new_with_joker(cards, bid));
-    }
+pub fn extract_input_01(input: &str) -> Result<Vec<Hand>, ParseError> {
+    extract_input(input, Hand::new)
+}
 
-    hands
+pub fn extract_input_02(input: &str) -> Result<Vec<Hand>, ParseError> {
+    extract_input(input, Hand::new_with_joker)
 }
 
-fn assignment01(input: &str) -> usize {
-    const ORDERING: &str = "AKQJT98765432";
-    let mut hands = extract_input_01(input);
+pub fn assignment01(input: &str) -> Solution {
+    let mut hands = extract_input_01(input)?;
 
-    hands.sort_by(|a, b| a.cmp(&b, ORDERING));
+    hands.sort_by(|a, b| a.compare_with(b, ORDERING));
 
-    let mut score = 1;
-    let mut total_winnings = 0;
+    let mut total_winnings: usize = 0;
 
-    for hand in hands {
-        total_winnings = total_winnings + (hand.bid * score);
-        score += 1;
+    for (rank, hand) in hands.iter().enumerate() {
+        let winnings = hand.bid.checked_mul(rank + 1).ok_or(Overflow)?;
+        total_winnings = total_winnings.checked_add(winnings).ok_or(Overflow)?;
     }
-    total_winnings
+    Ok(total_winnings.into())
 }
 
-fn assignment02(input: &str) -> usize {
-    const ORDERING: &str = "AKQT98765432J";
-    let mut hands = extract_input_02(input);
+pub fn assignment02(input: &str) -> Solution {
+    let mut hands = extract_input_02(input)?;
 
-    hands.sort_by(|a, b| a.cmp(&b, ORDERING));
+    hands.sort_by(|a, b| a.compare_with(b, JOKER_ORDERING));
 
-    let mut score = 1;
-    let mut total_winnings = 0;
+    let mut total_winnings: usize = 0;
 
-    for hand in hands {
-        total_winnings = total_winnings + (hand.bid * score);
-        score += 1;
+    for (rank, hand) in hands.iter().enumerate() {
+        let winnings = hand.bid.checked_mul(rank + 1).ok_or(Overflow)?;
+        total_winnings = total_winnings.checked_add(winnings).ok_or(Overflow)?;
     }
-    total_winnings
+    Ok(total_winnings.into())
 }
 
-pub fn day07() {
-    let input = aoc_2023::read_input("input/day07.txt").expect("Failed to read file");
-
-    let result_01 = assignment01(&input);
-    let result_02 = assignment02(&input);
+pub fn day07(input_file: &str) {
+    let input = crate::read_input(input_file).expect("Failed to read file");
 
-    println!("Day 07 - Assignment 01 solution: {result_01}");
-    println!("Day 07 - Assignment 02 solution: {result_02}");
+    answer::print_solution(7, 1, &assignment01(&input));
+    answer::print_solution(7, 2, &assignment02(&input));
     println!("\n");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answer::Answer;
+    use crate::{generate, property};
+
+    /// A higher strength is a better hand type.
+    fn naive_strength(cards: &str) -> usize {
+        let mut counts: Vec<usize> = cards
+            .chars()
+            .map(|card| cards.matches(card).count())
+            .collect();
+        counts.sort();
+        match counts.as_slice() {
+            [.., 5] => 6,
+            [.., 4] => 5,
+            [2, 2, 3, 3, 3] => 4,
+            [.., 3] => 3,
+            [.., 2, 2, 2, 2] => 2,
+            [.., 2] => 1,
+            _ => 0,
+        }
+    }
 
-    const EXAMPLE_DATA: &'static str = r"32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483
-";
+    /// The best hand type when all jokers are replaced by the same other card.
+    fn naive_joker_strength(cards: &str) -> usize {
+        "AKQT98765432"
+            .chars()
+            .map(|card| naive_strength(&cards.replace('J', &card.to_string())))
+            .max()
+            .unwrap()
+    }
+
+    fn naive_winnings(input: &str, ordering: &str, strength: fn(&str) -> usize) -> usize {
+        let mut hands: Vec<(usize, Vec<usize>, usize)> = input
+            .lines()
+            .map(|line| {
+                let (cards, bid) = line.split_once(' ').unwrap();
+                // a lower position in the ordering is a better card
+                let values = cards
+                    .chars()
+                    .map(|c| 12 - ordering.find(c).unwrap())
+                    .collect();
+                (strength(cards), values, bid.parse().unwrap())
+            })
+            .collect();
+        hands.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        hands
+            .iter()
+            .enumerate()
+            .map(|(rank, (_, _, bid))| (rank + 1) * bid)
+            .sum()
+    }
+
+    #[test]
+    fn hand_line_test() {
+        let hands = |input| parser::parse_all(block(hand_line()), input);
+        assert_eq!(
+            hands("32T3K 765\nKK677 28"),
+            Ok(vec![("32T3K", 765), ("KK677", 28)])
+        );
+
+        for (input, position, message) in [
+            ("ABCDEFG 1", (1, 2), "Unknown card 'B'"),
+            ("32T3K 765\nKK67 28", (2, 1), "Expected 5 cards, found 4"),
+            ("32T3K 765\nKK6777 28", (2, 1), "Expected 5 cards, found 6"),
+            ("32t3K 765", (1, 3), "Unknown card 't'"),
+            ("x2T3K 765", (1, 1), "Expected a card"),
+        ] {
+            let error = hands(input).unwrap_err();
+            assert_eq!((error.line, error.column), position, "{input}");
+            assert_eq!(error.message, message);
+        }
+    }
 
     #[test]
-    fn assignment01_test() {
-        assert_eq!(assignment01(&EXAMPLE_DATA.to_owned()), 6440);
+    fn round_trip_test() {
+        property::check(100, generate::day07, |input| {
+            let hands: Vec<String> = extract_input_01(input)
+                .unwrap()
+                .iter()
+                .map(|hand| format!("{} {}", hand.cards.iter().collect::<String>(), hand.bid))
+                .collect();
+            assert_eq!(hands, input.lines().collect::<Vec<&str>>());
+        });
     }
 
     #[test]
-    fn assignment02_test() {
-        assert_eq!(assignment02(&EXAMPLE_DATA.to_owned()), 5905);
+    fn reference_test() {
+        property::check(100, generate::day07, |input| {
+            let expected = naive_winnings(input, ORDERING, naive_strength);
+            assert_eq!(assignment01(input), Ok(Answer::from(expected)));
+            let expected = naive_winnings(input, JOKER_ORDERING, naive_joker_strength);
+            assert_eq!(assignment02(input), Ok(Answer::from(expected)));
+        });
     }
 }
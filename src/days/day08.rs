@@ -1,11 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
+use crate::answer::{self, Error, Overflow, Solution};
+use crate::math::{self, CrtError};
+use crate::parser::{
+    self, block, delimited, line, map, pair, position, preceded, space0, tag, take_while1, word,
+    Input, ParseError, Parser,
+};
+
 #[derive(Debug, Clone)]
-struct Node {
-    current: String,
-    left: String,
-    right: String,
+pub struct Node {
+    pub(crate) current: String,
+    pub(crate) left: String,
+    pub(crate) right: String,
 }
 
 impl PartialEq for Node {
@@ -14,134 +21,916 @@ impl PartialEq for Node {
     }
 }
 
+/// `AAA = (BBB, CCC)`
+fn node<'a>() -> impl Parser<'a, Node> {
+    let children = pair(word(), preceded(pair(tag(","), space0()), word()));
+    let children = delimited(pair(tag("("), space0()), children, pair(space0(), tag(")")));
+    let equals = delimited(space0(), tag("="), space0());
+    map(
+        pair(word(), preceded(equals, children)),
+        |(current, (left, right))| Node {
+            current: current.to_owned(),
+            left: left.to_owned(),
+            right: right.to_owned(),
+        },
+    )
+}
+
+fn directions<'a>() -> impl Parser<'a, Vec<char>> {
+    let directions = take_while1(|c| c == 'L' || c == 'R', "directions 'L' or 'R'");
+    line(map(directions, |directions| directions.chars().collect()))
+}
+
 impl FromStr for Node {
-    type Err = &'static str;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut line_it = s.split_terminator('=');
-        let current = line_it.next().unwrap_or_default().trim().to_owned();
-        let rest = line_it.last().unwrap_or_default().trim();
+        parser::parse_all(node(), s)
+    }
+}
 
-        let mut node_it = rest.split_terminator(',');
+pub fn get_directions(line: &str) -> Result<Vec<char>, ParseError> {
+    parser::parse_all(directions(), line)
+}
 
-        let mut left = node_it.next().unwrap_or_default().trim();
-        if left.contains('(') {
-            left = left.strip_prefix('(').unwrap();
-        }
+/// Parses all nodes of the network. Every node may only be defined once
+/// and every child has to refer to a node which is part of the network.
+/// The error of a network with dangling children lists all of them, and
+/// points at the first node referring to one.
+pub fn get_nodes(input: &str) -> Result<HashMap<String, Node>, ParseError> {
+    let network = preceded(block(directions()), block(line(pair(position(), node()))));
+    let lines = parser::parse_all(network, input)?;
 
-        let mut right = node_it.last().unwrap_or_default().trim();
-        if right.contains(')') {
-            right = right.strip_suffix(')').unwrap();
+    let mut nodes: HashMap<String, Node> = HashMap::new();
+    for (position, node) in &lines {
+        if nodes.contains_key(&node.current) {
+            let message = format!("Node '{}' is defined more than once", node.current);
+            return Err(position.error(message));
         }
+        nodes.insert(node.current.clone(), node.clone());
+    }
 
-        Ok(Self {
-            current,
-            left: left.to_owned(),
-            right: right.to_owned(),
-        })
+    let mut first: Option<Input> = None;
+    let mut references: Vec<String> = vec![];
+    for (position, node) in &lines {
+        for child in [&node.left, &node.right] {
+            if !nodes.contains_key(child) {
+                first.get_or_insert(*position);
+                let subject = if references.is_empty() {
+                    "Node"
+                } else {
+                    "node"
+                };
+                references.push(format!(
+                    "{subject} '{}' refers to unknown node '{child}'",
+                    node.current
+                ));
+            }
+        }
+    }
+    match first {
+        Some(position) => Err(position.error(references.join("; "))),
+        None => Ok(nodes),
     }
 }
 
-fn get_directions(line: &str) -> Vec<char> {
-    let mut directions: Vec<char> = vec![];
-    for char in line.chars() {
-        if char.is_alphabetic() {
-            directions.push(char);
+/// Problems of a network which don't prevent parsing it.
+#[derive(Debug, Default, PartialEq)]
+pub struct NetworkReport {
+    /// Nodes which are referenced as a child, but never defined. `get_nodes` rejects
+    /// those, so they only show up in networks assembled from single `Node`s.
+    pub dangling: Vec<String>,
+    /// Nodes which can't be reached from the start node.
+    pub unreachable: Vec<String>,
+}
+
+impl NetworkReport {
+    pub fn new(nodes: &HashMap<String, Node>, start: &str) -> NetworkReport {
+        let mut dangling: Vec<String> = nodes
+            .values()
+            .flat_map(|node| [&node.left, &node.right])
+            .filter(|child| !nodes.contains_key(*child))
+            .cloned()
+            .collect();
+        dangling.sort();
+        dangling.dedup();
+
+        let mut reached: HashSet<&str> = HashSet::new();
+        let mut queue: Vec<&str> = vec![start];
+        while let Some(name) = queue.pop() {
+            if !reached.insert(name) {
+                continue;
+            }
+            if let Some(node) = nodes.get(name) {
+                queue.push(&node.left);
+                queue.push(&node.right);
+            }
+        }
+
+        let mut unreachable: Vec<String> = nodes
+            .keys()
+            .filter(|name| !reached.contains(name.as_str()))
+            .cloned()
+            .collect();
+        unreachable.sort();
+
+        NetworkReport {
+            dangling,
+            unreachable,
         }
     }
-    directions
 }
 
-fn get_nodes(lines: &str) -> HashMap<String, Node> {
-    let mut nodes: HashMap<String, Node> = HashMap::new();
+/// The network with every node name interned into a dense id.
+/// The children of node `id` are stored at `children[id]` as `[left, right]`.
+#[derive(Debug, Default)]
+pub struct Network {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+    children: Vec<[u32; 2]>,
+}
+
+impl Network {
+    /// Expects a network from `get_nodes`, where every child is part of the network.
+    pub fn from_nodes(nodes: &HashMap<String, Node>) -> Network {
+        let mut names: Vec<String> = nodes.keys().cloned().collect();
+        names.sort();
+
+        let ids: HashMap<String, u32> = names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (name.clone(), id as u32))
+            .collect();
+
+        let children = names
+            .iter()
+            .map(|name| {
+                let node = &nodes[name];
+                [ids[&node.left], ids[&node.right]]
+            })
+            .collect();
+
+        Network {
+            names,
+            ids,
+            children,
+        }
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
 
-    for line in lines.lines() {
-        if !line.contains('=') {
-            continue;
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn next(&self, id: u32, direction: char) -> u32 {
+        let side = usize::from(direction == 'R');
+        self.children[id as usize][side]
+    }
+
+    /// Follows the directions from `start` until a node matching `is_goal` is reached
+    /// and returns the amount of steps taken.
+    ///
+    /// There are only `nodes * directions` different states during the walk. If no goal
+    /// was found after that many steps, the walk is stuck in a loop without a goal.
+    pub fn walk(
+        &self,
+        directions: &[char],
+        start: u32,
+        is_goal: impl Fn(&str) -> bool,
+    ) -> Result<usize, String> {
+        if directions.is_empty() {
+            return Err("There are no directions to follow".to_owned());
         }
-        if let Ok(node) = Node::from_str(line) {
-            nodes.insert(node.current.to_owned(), node);
+        let max_steps = self.names.len() * directions.len();
+
+        let mut current = start;
+        for steps in 0..=max_steps {
+            if is_goal(self.name(current)) {
+                return Ok(steps);
+            }
+            current = self.next(current, directions[steps % directions.len()]);
         }
+        Err(format!(
+            "No goal can be reached from '{}'",
+            self.name(start)
+        ))
     }
-    nodes
-}
 
-fn assignment01(input: &str) -> usize {
-    let direction_str = input.lines().next().expect("Invalid input");
-    let directions = get_directions(direction_str);
-    let all_nodes = get_nodes(&input);
+    /// Like `walk`, but returns every node on the way from `start` to the goal.
+    pub fn trace(
+        &self,
+        directions: &[char],
+        start: u32,
+        is_goal: impl Fn(&str) -> bool,
+    ) -> Result<Vec<u32>, String> {
+        let steps = self.walk(directions, start, is_goal)?;
 
-    let mut i = 0;
-    let mut path: Vec<&Node> = vec![];
-    let mut curr_node: &Node = all_nodes.get("AAA").expect("There must be a starting node");
-    let mut nodes_traversed = 0;
+        let mut path = vec![start];
+        for step in 0..steps {
+            path.push(self.next(path[step], directions[step % directions.len()]));
+        }
+        Ok(path)
+    }
 
-    path.push(curr_node);
+    /// Exports the network in the DOT format of graphviz, e.g. to render it with
+    /// `dot -Tsvg network.dot -o network.svg`.
+    ///
+    /// Start nodes are filled green, goal nodes red and the edges of `path` are drawn in blue.
+    pub fn to_dot(
+        &self,
+        is_start: impl Fn(&str) -> bool,
+        is_goal: impl Fn(&str) -> bool,
+        path: &[u32],
+    ) -> String {
+        let visited: HashSet<u32> = path.iter().copied().collect();
+        let taken: HashSet<(u32, u32)> = path.windows(2).map(|edge| (edge[0], edge[1])).collect();
 
-    loop {
-        if i >= directions.len() {
-            // restart from the beginning
-            i = 0;
+        // names like `11A` aren't valid DOT IDs without quotes
+        let mut dot = String::from("digraph network {\n");
+        for (id, name) in self.names.iter().enumerate() {
+            if is_start(name) {
+                dot += &format!("    \"{name}\" [style=filled, fillcolor=palegreen];\n");
+            } else if is_goal(name) {
+                dot += &format!("    \"{name}\" [style=filled, fillcolor=lightcoral];\n");
+            } else if visited.contains(&(id as u32)) {
+                dot += &format!("    \"{name}\" [color=blue];\n");
+            }
         }
-        let direction = directions.get(i).expect("Expected a direction");
-        if *direction == 'L' {
-            curr_node = all_nodes.get(curr_node.left.as_str()).unwrap();
-        } else if *direction == 'R' {
-            curr_node = all_nodes.get(curr_node.right.as_str()).unwrap();
+
+        for (id, name) in self.names.iter().enumerate() {
+            let [left, right] = self.children[id];
+            let edges = if left == right {
+                vec![(left, "L,R")]
+            } else {
+                vec![(left, "L"), (right, "R")]
+            };
+
+            for (child, label) in edges {
+                let child_name = self.name(child);
+                if taken.contains(&(id as u32, child)) {
+                    dot += &format!(
+                        "    \"{name}\" -> \"{child_name}\" [label=\"{label}\", color=blue, penwidth=2];\n"
+                    );
+                } else {
+                    dot += &format!("    \"{name}\" -> \"{child_name}\" [label=\"{label}\"];\n");
+                }
+            }
+        }
+        dot += "}\n";
+        dot
+    }
+
+    /// All nodes whose name matches the predicate, e.g. every node ending with 'A'.
+    pub fn find(&self, predicate: impl Fn(&str) -> bool) -> Vec<u32> {
+        (0..self.names.len() as u32)
+            .filter(|id| predicate(self.name(*id)))
+            .collect()
+    }
+
+    /// Walks from `start` until a state of (node, instruction index) repeats.
+    pub fn cycle(
+        &self,
+        directions: &[char],
+        start: u32,
+        is_goal: impl Fn(&str) -> bool,
+    ) -> Result<Cycle, String> {
+        if directions.is_empty() {
+            return Err("There are no directions to follow".to_owned());
         }
+        let mut seen: HashMap<(u32, usize), usize> = HashMap::new();
+        let mut goals: Vec<usize> = vec![];
 
-        if path.contains(&curr_node) {
-            while path.contains(&curr_node) {
-                path.pop();
+        let mut current = start;
+        let mut steps = 0;
+        loop {
+            let instruction = steps % directions.len();
+            if let Some(first_seen) = seen.insert((current, instruction), steps) {
+                return Ok(Cycle {
+                    start,
+                    tail: first_seen,
+                    length: steps - first_seen,
+                    goals,
+                });
             }
+            if is_goal(self.name(current)) {
+                goals.push(steps);
+            }
+            current = self.next(current, directions[instruction]);
+            steps += 1;
+        }
+    }
+}
+
+/// The shape of the walk from a start node: after `tail` steps the walk
+/// enters a cycle of `length` steps, which repeats forever.
+#[derive(Debug, PartialEq)]
+pub struct Cycle {
+    start: u32,
+    tail: usize,
+    length: usize,
+    /// Steps at which a goal is reached, up to the end of the first round through the cycle.
+    goals: Vec<usize>,
+}
+
+impl Cycle {
+    /// The node the walk starts at.
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    pub fn tail(&self) -> usize {
+        self.tail
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Steps at which a goal is reached, up to the end of the first round through the cycle.
+    pub fn goals(&self) -> &[usize] {
+        &self.goals
+    }
+
+    /// Positions of the goals within the cycle, counted from the start of the cycle.
+    pub fn goals_in_cycle(&self) -> Vec<usize> {
+        self.goals
+            .iter()
+            .filter(|step| **step >= self.tail)
+            .map(|step| step - self.tail)
+            .collect()
+    }
+
+    /// The LCM shortcut assumes that a goal is reached after exactly every `period` steps,
+    /// starting at step `period`. Returns that period if the assumption holds for this walk.
+    pub fn period(&self) -> Option<usize> {
+        let period = *self.goals.iter().find(|step| **step > 0)?;
+        if !self.length.is_multiple_of(period) {
+            return None;
+        }
+        // the goals repeat with the length of the cycle, which is a multiple of
+        // the period, so checking one step past the end of the cycle is enough.
+        // That step is the start of the cycle again, which is step 0 without a tail.
+        (1..=self.tail + self.length)
+            .all(|step| self.is_goal_at(step) == step.is_multiple_of(period))
+            .then_some(period)
+    }
+
+    /// True if the walk is at a goal after `step` steps.
+    pub fn is_goal_at(&self, step: usize) -> bool {
+        if step < self.tail + self.length {
+            self.goals.contains(&step)
         } else {
-            path.push(curr_node);
+            let offset = (step - self.tail) % self.length;
+            self.goals_in_cycle().contains(&offset)
         }
+    }
+}
+
+/// The first step at which all walks are at a goal at the same time, or `None` if that
+/// never happens. Uses the LCM of the periods if every walk is periodic, otherwise
+/// every combination of goals within the cycles is solved with the CRT.
+pub fn first_common_goal(cycles: &[Cycle]) -> Result<Option<u64>, Overflow> {
+    // without any walks, all of them are at a goal right away
+    if cycles.is_empty() {
+        return Ok(Some(0));
+    }
+    let periods: Option<Vec<u64>> = cycles
+        .iter()
+        .map(|cycle| cycle.period().map(|period| period as u64))
+        .collect();
+    if let Some(periods) = periods {
+        return math::lcm_all(periods).map(Some).ok_or(Overflow);
+    }
 
-        nodes_traversed += 1;
+    // until every walk has entered its cycle, the steps are checked one by one
+    let tail = cycles.iter().map(|cycle| cycle.tail).max().unwrap_or(0);
+    if let Some(step) = (0..tail).find(|step| cycles.iter().all(|cycle| cycle.is_goal_at(*step))) {
+        return Ok(Some(step as u64));
+    }
 
-        if let Some(node) = path.last() {
-            if node.current == "ZZZ" {
-                break;
+    // every partial system solves the walks seen so far as `step = residue mod modulus`
+    let mut partials: Vec<(u64, u64)> = vec![(0, 1)];
+    for cycle in cycles {
+        let length = cycle.length as u64;
+        let congruences: Vec<(u64, u64)> = cycle
+            .goals_in_cycle()
+            .into_iter()
+            .map(|offset| ((cycle.tail + offset) as u64 % length, length))
+            .collect();
+        let mut combined = Vec::new();
+        for partial in &partials {
+            for congruence in &congruences {
+                match math::crt([*partial, *congruence]) {
+                    Ok(solution) => combined.push(solution),
+                    Err(CrtError::Incompatible) => continue,
+                    Err(_) => return Err(Overflow),
+                }
             }
         }
-        i += 1;
+        combined.sort_unstable();
+        combined.dedup();
+        if combined.is_empty() {
+            return Ok(None);
+        }
+        partials = combined;
+    }
+
+    let mut first: Option<u64> = None;
+    for (mut step, modulus) in partials {
+        // the congruences only hold once every walk is within its cycle
+        if step < tail as u64 {
+            let rounds = (tail as u64 - step).div_ceil(modulus);
+            step = rounds
+                .checked_mul(modulus)
+                .and_then(|offset| offset.checked_add(step))
+                .ok_or(Overflow)?;
+        }
+        first = Some(first.map_or(step, |first| first.min(step)));
     }
-    nodes_traversed
+    Ok(first)
 }
 
-fn assignment02(input: &str) -> u32 {
-    0
+/// The directions of the first line and the network of the nodes after them.
+fn parse_network(input: &str) -> Result<(Vec<char>, Network), ParseError> {
+    let directions = get_directions(input.lines().next().unwrap_or_default())?;
+    let network = Network::from_nodes(&get_nodes(input)?);
+    Ok((directions, network))
 }
 
-pub fn day08() {
-    let input = aoc_2023::read_input("input/day08.txt").expect("Failed to read file");
+pub fn assignment01(input: &str) -> Solution {
+    let (directions, network) = parse_network(input)?;
 
-    let result_01 = assignment01(&input);
-    let result_02 = assignment02(&input);
+    let start = network
+        .id("AAA")
+        .ok_or_else(|| Error::Unsolvable("There is no node 'AAA'".to_owned()))?;
+    let steps = network
+        .walk(&directions, start, |name| name == "ZZZ")
+        .map_err(Error::Unsolvable)?;
+    Ok(steps.into())
+}
+
+pub fn ghost_cycles(input: &str) -> Result<(Network, Vec<Cycle>), Error> {
+    let (directions, network) = parse_network(input)?;
+
+    let cycles = network
+        .find(|name| name.ends_with('A'))
+        .into_iter()
+        .map(|start| network.cycle(&directions, start, |name| name.ends_with('Z')))
+        .collect::<Result<Vec<Cycle>, String>>()
+        .map_err(Error::Unsolvable)?;
+    Ok((network, cycles))
+}
+
+pub fn assignment02(input: &str) -> Solution {
+    let (_, cycles) = ghost_cycles(input)?;
+
+    let steps = first_common_goal(&cycles)?.ok_or_else(|| {
+        Error::Unsolvable("The ghosts never reach their goals together".to_owned())
+    })?;
+    Ok(steps.into())
+}
+
+/// Writes the network as DOT file, with the path of the first assignment highlighted.
+fn export_dot(input: &str, file: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (directions, network) = parse_network(input)?;
+
+    let path = network
+        .id("AAA")
+        .and_then(|start| network.trace(&directions, start, |name| name == "ZZZ").ok())
+        .unwrap_or_default();
+
+    let dot = network.to_dot(|name| name == "AAA", |name| name == "ZZZ", &path);
+    std::fs::write(file, dot)?;
+    Ok(())
+}
 
-    println!("Day 08 - Assignment 01 solution: {result_01}");
-    println!("Day 08 - Assignment 02 solution: {result_02}");
+pub fn day08(input_file: &str, dot_file: Option<&str>) {
+    let input = crate::read_input(input_file).expect("Failed to read file");
+
+    answer::print_solution(8, 1, &assignment01(&input));
+    answer::print_solution(8, 2, &assignment02(&input));
+
+    // a broken input was already reported by the assignments
+    if let Ok(nodes) = get_nodes(&input) {
+        let report = NetworkReport::new(&nodes, "AAA");
+        if !report.unreachable.is_empty() {
+            println!(
+                "Day 08 - {} nodes can't be reached from AAA",
+                report.unreachable.len()
+            );
+        }
+    }
+    if let Ok((network, cycles)) = ghost_cycles(&input) {
+        for cycle in cycles {
+            println!(
+                "Day 08 - Walk from {}: tail {}, cycle {}, goals in cycle at {:?}",
+                network.name(cycle.start),
+                cycle.tail,
+                cycle.length,
+                cycle.goals_in_cycle()
+            );
+        }
+    }
+    if let Some(file) = dot_file {
+        match export_dot(&input, file) {
+            Ok(()) => println!("Day 08 - Network written to {file}"),
+            Err(error) => println!("Day 08 - Network not written: {error}"),
+        }
+    }
     println!("\n");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answer::Answer;
+    use crate::{generate, property};
+
+    const EXAMPLE_DATA: &str = include_str!("../../fixtures/day08/example.txt");
+
+    #[test]
+    fn node_from_str_test() {
+        let node = Node::from_str("AAA = (BBB, CCC)").unwrap();
+        assert_eq!(node.left, "BBB");
+        assert_eq!(node.right, "CCC");
+        assert!(Node::from_str("AAA = BBB").is_err());
+        assert!(Node::from_str("AAA = (BBB)").is_err());
+        assert!(Node::from_str("AAA = (BBB, CCC").is_err());
+        assert!(Node::from_str("AAA (BBB, CCC)").is_err());
+        assert!(Node::from_str("= (BBB, CCC)").is_err());
+
+        let error = Node::from_str("AAA = (BBB, CCC) DDD").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 18));
+    }
+
+    #[test]
+    fn get_nodes_test() {
+        let error = get_nodes("LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, QQQ)\n").unwrap_err();
+        assert_eq!(error.line, 4);
+        assert_eq!(error.message, "Node 'BBB' refers to unknown node 'QQQ'");
+
+        // every dangling child is reported, not just the first one
+        let error =
+            get_nodes("LR\n\nAAA = (BBB, BBB)\nBBB = (QQQ, AAA)\nCCC = (AAA, RRR)\n").unwrap_err();
+        assert_eq!(error.line, 4);
+        assert_eq!(
+            error.message,
+            "Node 'BBB' refers to unknown node 'QQQ'; node 'CCC' refers to unknown node 'RRR'"
+        );
+
+        let error = get_nodes("LR\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)\n").unwrap_err();
+        assert_eq!(error.line, 4);
+
+        let error = get_nodes("LXR\n\nAAA = (AAA, AAA)\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
+    }
+
+    #[test]
+    fn network_report_test() {
+        let mut nodes = get_nodes(EXAMPLE_DATA).unwrap();
+        assert_eq!(NetworkReport::new(&nodes, "AAA"), NetworkReport::default());
 
-    const EXAMPLE_DATA: &'static str = r"LLR
+        for line in ["CCC = (ZZZ, DDD)", "DDD = (CCC, EEE)"] {
+            let node = Node::from_str(line).unwrap();
+            nodes.insert(node.current.clone(), node);
+        }
+        let report = NetworkReport::new(&nodes, "AAA");
+        assert_eq!(report.dangling, vec!["EEE"]);
+        assert_eq!(report.unreachable, vec!["CCC", "DDD"]);
+    }
+
+    #[test]
+    fn network_test() {
+        let network = Network::from_nodes(&get_nodes(EXAMPLE_DATA).unwrap());
+        let start = network.id("AAA").unwrap();
+        let next = network.next(start, 'L');
+        assert_eq!(network.name(next), "BBB");
+        assert_eq!(network.name(network.next(next, 'R')), "ZZZ");
+        assert_eq!(network.id("CCC"), None);
+    }
+
+    #[test]
+    fn walk_test() {
+        let directions = get_directions("LR").unwrap();
+        let network = Network::from_nodes(&get_nodes(EXAMPLE_DATA).unwrap());
+        let start = network.id("AAA").unwrap();
+
+        assert_eq!(
+            network.walk(&directions, start, |name| name == "AAA"),
+            Ok(0)
+        );
+        assert_eq!(
+            network.walk(&directions, start, |name| name == "ZZZ"),
+            Ok(2)
+        );
+        assert_eq!(
+            network.walk(&directions, start, |name| name.ends_with('B')),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn walk_unreachable_goal_test() {
+        const DATA: &str = r"LR
 
 AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
+BBB = (AAA, CCC)
+CCC = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
 
+        let directions = get_directions("LR").unwrap();
+        let network = Network::from_nodes(&get_nodes(DATA).unwrap());
+        let start = network.id("AAA").unwrap();
+
+        // AAA -L-> BBB -R-> CCC -L-> AAA, ZZZ is only reached with R from CCC
+        let error = network.walk(&directions, start, |name| name == "ZZZ");
+        assert_eq!(error, Err("No goal can be reached from 'AAA'".to_owned()));
+
+        let directions = get_directions("LRR").unwrap();
+        assert_eq!(
+            network.walk(&directions, start, |name| name == "ZZZ"),
+            Ok(3)
+        );
+        assert!(network.walk(&[], start, |name| name == "ZZZ").is_err());
+
+        assert_eq!(
+            assignment01(DATA),
+            Err(Error::Unsolvable(
+                "No goal can be reached from 'AAA'".to_owned()
+            ))
+        );
+        assert!(matches!(
+            assignment01("LR\n\nBBB = (BBB, BBB)\n"),
+            Err(Error::Unsolvable(_))
+        ));
+    }
+
+    #[test]
+    fn to_dot_test() {
+        let directions = get_directions("LR").unwrap();
+        let network = Network::from_nodes(&get_nodes(EXAMPLE_DATA).unwrap());
+        let start = network.id("AAA").unwrap();
+        let path = network
+            .trace(&directions, start, |name| name == "ZZZ")
+            .unwrap();
+        assert_eq!(path.len(), 3);
+
+        let dot = network.to_dot(|name| name == "AAA", |name| name == "ZZZ", &path);
+        assert_eq!(
+            dot,
+            r#"digraph network {
+    "AAA" [style=filled, fillcolor=palegreen];
+    "BBB" [color=blue];
+    "ZZZ" [style=filled, fillcolor=lightcoral];
+    "AAA" -> "BBB" [label="L,R", color=blue, penwidth=2];
+    "BBB" -> "AAA" [label="L"];
+    "BBB" -> "ZZZ" [label="R", color=blue, penwidth=2];
+    "ZZZ" -> "ZZZ" [label="L,R"];
+}
+"#
+        );
+
+        let network = Network::from_nodes(&get_nodes(GHOST_DATA).unwrap());
+        let dot = network.to_dot(|name| name.ends_with('A'), |name| name.ends_with('Z'), &[]);
+        assert!(dot.contains("    \"11A\" [style=filled, fillcolor=palegreen];\n"));
+        assert!(dot.contains("    \"11A\" -> \"11B\" [label=\"L\"];\n"));
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn network_benchmark() {
+        use std::time::Instant;
+
+        const NODES: u32 = 100_000;
+        const STEPS: usize = 10_000_000;
+
+        // names with 4 letters, children picked by a linear congruential generator
+        let name = |id: u32| -> String {
+            (0..4)
+                .map(|i| (b'A' + (id / 26u32.pow(i) % 26) as u8) as char)
+                .collect()
+        };
+        let mut seed: u64 = 2023;
+        let mut random = |max: u32| -> u32 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((seed >> 33) % max as u64) as u32
+        };
+
+        let mut input = String::from("LRRLRLLLRRLR\n\n");
+        for id in 0..NODES {
+            let (left, right) = (random(NODES), random(NODES));
+            input += &format!("{} = ({}, {})\n", name(id), name(left), name(right));
+        }
+        let directions = get_directions(input.lines().next().unwrap()).unwrap();
+        let nodes = get_nodes(&input).unwrap();
+        let network = Network::from_nodes(&nodes);
+
+        let start = Instant::now();
+        let mut current = &nodes[&name(0)];
+        for step in 0..STEPS {
+            current = match directions[step % directions.len()] {
+                'L' => &nodes[&current.left],
+                _ => &nodes[&current.right],
+            };
+        }
+        let string_duration = start.elapsed();
+        let string_result = current.current.clone();
+
+        let start = Instant::now();
+        let mut current = network.id(&name(0)).unwrap();
+        for step in 0..STEPS {
+            current = network.next(current, directions[step % directions.len()]);
+        }
+        let id_duration = start.elapsed();
+
+        assert_eq!(network.name(current), string_result);
+        println!("String keys: {string_duration:?}, interned ids: {id_duration:?}");
+    }
+
+    const GHOST_DATA: &str = include_str!("../../fixtures/day08/ghosts.txt");
+
+    #[test]
+    fn cycle_test() {
+        let (_, cycles) = ghost_cycles(GHOST_DATA).unwrap();
+
+        assert_eq!((cycles[0].tail, cycles[0].length), (1, 2));
+        assert_eq!(cycles[0].goals_in_cycle(), vec![1]);
+        assert_eq!(cycles[0].period(), Some(2));
+
+        // 22Z is reached every 3 steps, but the states only repeat with both directions
+        assert_eq!((cycles[1].tail, cycles[1].length), (1, 6));
+        assert_eq!(cycles[1].goals_in_cycle(), vec![2, 5]);
+        assert_eq!(cycles[1].period(), Some(3));
+    }
+
+    #[test]
+    fn many_goals_test() {
+        // walk i misses its goal only at the steps i, i + 16, ..., so all of them meet at step 15
+        let cycles: Vec<Cycle> = (0..15)
+            .map(|missing| Cycle {
+                start: missing as u32,
+                tail: 0,
+                length: 16,
+                goals: (0..16).filter(|step| *step != missing).collect(),
+            })
+            .collect();
+        assert_eq!(first_common_goal(&cycles), Ok(Some(15)));
+    }
+
     #[test]
-    fn assignment01_test() {
-        assert_eq!(assignment01(&EXAMPLE_DATA.to_owned()), 6);
+    fn cycle_without_lcm_test() {
+        // the goal is first reached after 2 steps, but then every 3 steps
+        const DATA: &str = r"L
+
+AAA = (BBB, BBB)
+BBB = (CCZ, CCZ)
+CCZ = (DDD, DDD)
+DDD = (EEE, EEE)
+EEE = (CCZ, CCZ)";
+
+        let directions = get_directions("L").unwrap();
+        let network = Network::from_nodes(&get_nodes(DATA).unwrap());
+        let start = network.id("AAA").unwrap();
+        let cycle = network
+            .cycle(&directions, start, |name| name.ends_with('Z'))
+            .unwrap();
+
+        assert_eq!((cycle.tail, cycle.length), (2, 3));
+        assert!(network.cycle(&[], start, |_| true).is_err());
+        assert_eq!(cycle.goals_in_cycle(), vec![0]);
+        assert_eq!(cycle.period(), None);
+        assert!(cycle.is_goal_at(2) && cycle.is_goal_at(11) && !cycle.is_goal_at(12));
+        assert_eq!(first_common_goal(&[cycle]), Ok(Some(2)));
+
+        // without a tail the start is part of the cycle, but not a goal
+        let network =
+            Network::from_nodes(&get_nodes("L\n\nAAA = (AAZ, AAZ)\nAAZ = (AAA, AAA)").unwrap());
+        let start = network.id("AAA").unwrap();
+        let cycle = network
+            .cycle(&directions, start, |name| name.ends_with('Z'))
+            .unwrap();
+        assert_eq!((cycle.tail, cycle.length), (0, 2));
+        assert_eq!(cycle.period(), None);
     }
 
-    // #[test]
-    // fn assignment02_test() {
-    //     assert_eq!(assignment02(&EXAMPLE_DATA.to_owned()), 71503);
-    // }
+    #[test]
+    fn first_common_goal_test() {
+        // AAA reaches its goal after 2, 5, 8, ... steps and 11A after 4, 8, ... steps
+        const DATA: &str = r"L
+
+AAA = (BBB, BBB)
+BBB = (CCZ, CCZ)
+CCZ = (DDD, DDD)
+DDD = (EEE, EEE)
+EEE = (CCZ, CCZ)
+11A = (11B, 11B)
+11B = (11C, 11C)
+11C = (11D, 11D)
+11D = (11Z, 11Z)
+11Z = (11B, 11B)";
+
+        assert_eq!(assignment02(DATA), Ok(Answer::from(8)));
+
+        let (_, cycles) = ghost_cycles(GHOST_DATA).unwrap();
+        assert_eq!(first_common_goal(&cycles), Ok(Some(6)));
+        assert_eq!(first_common_goal(&[]), Ok(Some(0)));
+
+        // AAA is at its goal after even steps and 11A after odd steps
+        const NEVER: &str = r"L
+
+AAA = (AAB, AAB)
+AAB = (AAZ, AAZ)
+AAZ = (AAB, AAB)
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)";
+
+        assert_eq!(
+            assignment02(NEVER),
+            Err(Error::Unsolvable(
+                "The ghosts never reach their goals together".to_owned()
+            ))
+        );
+        // AAA starts within its cycle, so after 2 steps it isn't at a goal
+        const WITHOUT_TAIL: &str = r"L
+
+AAA = (AAZ, AAZ)
+AAZ = (AAA, AAA)
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)";
+
+        assert!(assignment02(WITHOUT_TAIL).is_err());
+    }
+
+    /// The directions and the `(left, right)` children of every node.
+    fn naive_network(input: &str) -> (Vec<char>, HashMap<&str, (&str, &str)>) {
+        let mut lines = input.lines();
+        let directions = lines.next().unwrap().chars().collect();
+        let nodes = lines
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let (node, children) = line.split_once(" = ").unwrap();
+                let children = children.trim_matches(|c| c == '(' || c == ')');
+                (node, children.split_once(", ").unwrap())
+            })
+            .collect();
+        (directions, nodes)
+    }
+
+    /// Moves all walkers at the same time, until all of them are at a goal.
+    fn naive_walk(input: &str, is_start: fn(&str) -> bool, is_goal: fn(&str) -> bool) -> usize {
+        let (directions, nodes) = naive_network(input);
+        let mut current: Vec<&str> = nodes.keys().copied().filter(|n| is_start(n)).collect();
+        let mut steps = 0;
+        while !current.iter().all(|node| is_goal(node)) {
+            for node in current.iter_mut() {
+                let (left, right) = nodes[node];
+                *node = if directions[steps % directions.len()] == 'L' {
+                    left
+                } else {
+                    right
+                };
+            }
+            steps += 1;
+        }
+        steps
+    }
+
+    #[test]
+    fn round_trip_test() {
+        property::check(100, generate::day08, |input| {
+            let (directions, nodes) = naive_network(input);
+            assert_eq!(
+                get_directions(input.lines().next().unwrap()),
+                Ok(directions)
+            );
+
+            let parsed = get_nodes(input).unwrap();
+            assert_eq!(parsed.len(), nodes.len());
+            for (name, node) in parsed {
+                assert_eq!(
+                    nodes[name.as_str()],
+                    (node.left.as_str(), node.right.as_str())
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn reference_test() {
+        // small networks, so the ghosts meet after a few steps
+        let network =
+            |rng: &mut crate::random::Rng, size: usize| generate::day08(rng, size.min(16));
+        property::check(100, network, |input| {
+            let expected = naive_walk(input, |n| n == "AAA", |n| n == "ZZZ");
+            assert_eq!(assignment01(input), Ok(Answer::from(expected)));
+            let expected = naive_walk(input, |n| n.ends_with('A'), |n| n.ends_with('Z'));
+            assert_eq!(assignment02(input), Ok(Answer::from(expected)));
+        });
+    }
 }
@@ -1,115 +1,388 @@
-fn extract_sequences(input: &str) -> Vec<Vec<i64>> {
-    let mut sequences: Vec<Vec<i64>> = vec![];
-    for line in input.lines() {
-        let sequence: Vec<i64> = line
-            .split_whitespace()
-            .filter_map(|s| s.parse::<i64>().ok())
-            .collect();
-        sequences.push(sequence);
-    }
-    sequences
+use std::fmt;
+
+use crate::answer::{self, Error, Overflow, Solution};
+use crate::math::binomial;
+use crate::parser::{self, block, numeric_line, ParseError};
+
+pub(crate) fn extract_sequences(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    parser::parse_all(block(numeric_line::<i64>(None)), input)
 }
 
-fn calculate_sequence(sequence: Vec<i64>, subtraction: fn(i64, i64) -> i64) -> Option<Vec<i64>> {
-    let mut new_sequence: Vec<i64> = vec![];
+#[derive(Debug, PartialEq)]
+pub enum ExtrapolationError {
+    /// There are no values to extrapolate from.
+    Empty,
+    /// The differences of a history with several values never become all zero,
+    /// so it isn't described by a polynomial of a lower degree than its length.
+    NotPolynomial,
+    /// An intermediate result doesn't fit into an i128.
+    Overflow,
+}
 
-    let mut finished = true;
-    for number in &sequence {
-        if *number != 0 {
-            finished = false;
-            break;
+impl fmt::Display for ExtrapolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtrapolationError::Empty => write!(f, "the history is empty"),
+            ExtrapolationError::NotPolynomial => {
+                write!(f, "the differences of the history never become zero")
+            }
+            ExtrapolationError::Overflow => write!(f, "{Overflow}"),
         }
     }
+}
 
-    if finished {
-        return None;
-    }
+impl std::error::Error for ExtrapolationError {}
+
+/// All rows of differences of a history, starting with the history itself.
+/// The rows are calculated until one of them is all zeros or only has a single value left.
+#[derive(Debug, PartialEq)]
+pub struct DifferenceTable {
+    levels: Vec<Vec<i128>>,
+}
 
-    for i in 0..sequence.len() {
-        if sequence.len() <= i + 1 {
-            break;
+impl DifferenceTable {
+    pub fn new(sequence: &[i64]) -> Result<DifferenceTable, ExtrapolationError> {
+        if sequence.is_empty() {
+            return Err(ExtrapolationError::Empty);
         }
+        let mut levels: Vec<Vec<i128>> =
+            vec![sequence.iter().map(|value| *value as i128).collect()];
 
-        let next = i + 1;
-        new_sequence.push(subtraction(sequence[i], sequence[next]));
+        while let Some(last) = levels.last() {
+            if last.len() == 1 || last.iter().all(|value| *value == 0) {
+                break;
+            }
+            let differences = last
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]))
+                .collect::<Option<Vec<i128>>>()
+                .ok_or(ExtrapolationError::Overflow)?;
+            levels.push(differences);
+        }
+        Ok(DifferenceTable { levels })
     }
-    Some(new_sequence)
-}
 
-fn assignment01(input: &str) -> i64 {
-    let sequences = extract_sequences(input);
-    let mut sum = 0;
+    /// The rows of the table, the history itself first.
+    pub fn levels(&self) -> &[Vec<i128>] {
+        &self.levels
+    }
 
-    for sequence in sequences {
-        let mut current_sequence = Some(sequence);
-        let mut sequence_history: Vec<Vec<i64>> = vec![];
+    /// True if a row of only zeros was reached, i.e. the history is a polynomial
+    /// of a lower degree than its length. A single value is read as a constant.
+    pub fn is_polynomial(&self) -> bool {
+        self.levels.len() == 1
+            || self
+                .levels
+                .last()
+                .is_some_and(|level| level.iter().all(|value| *value == 0))
+    }
 
-        while let Some(next_sequence) = current_sequence {
-            sequence_history.push(next_sequence.clone());
-            if let Some(new_sequence) = calculate_sequence(next_sequence, |a, b| b - a) {
-                current_sequence = Some(new_sequence);
-            } else {
-                current_sequence = None;
-            }
+    /// The degree of the polynomial, constants and the zero polynomial have degree 0.
+    pub fn degree(&self) -> Option<usize> {
+        if self.is_polynomial() {
+            Some(self.levels.len().saturating_sub(2))
+        } else {
+            None
         }
+    }
+}
+
+impl fmt::Display for DifferenceTable {
+    /// Prints the rows as a pyramid, every row shifted by half a column.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .levels
+            .iter()
+            .flatten()
+            .map(|value| value.to_string().len())
+            .max()
+            .unwrap_or(1);
+        // an even column width, so every row can be shifted by exactly half a column
+        let column = (width + 2) / 2 * 2;
 
-        for history in sequence_history.iter().rev() {
-            sum += history.last().unwrap();
+        for (depth, level) in self.levels.iter().enumerate() {
+            let row: String = level
+                .iter()
+                .map(|value| format!("{value:>column$}"))
+                .collect();
+            writeln!(f, "{}{}", " ".repeat(depth * column / 2), row.trim_end())?;
         }
+        Ok(())
     }
-    sum
 }
 
-fn assignment02(input: &str) -> i64 {
-    let sequences = extract_sequences(input);
-    let mut sum = 0;
+/// The polynomial of lowest degree through all values of a history,
+/// where the first value is at `x = 0`, the second at `x = 1` and so on.
+#[derive(Debug, PartialEq)]
+pub struct Polynomial {
+    degree: usize,
+    values: Vec<i128>,
+}
+
+impl Polynomial {
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn fit(sequence: &[i64]) -> Result<Polynomial, ExtrapolationError> {
+        let table = DifferenceTable::new(sequence)?;
+        let degree = table.degree().ok_or(ExtrapolationError::NotPolynomial)?;
+        let values = table.levels.into_iter().next().unwrap_or_default();
 
-    for sequence in sequences {
-        let mut current_sequence = Some(sequence);
-        let mut sequence_history: Vec<Vec<i64>> = vec![];
+        Ok(Polynomial { degree, values })
+    }
+
+    /// Evaluates the polynomial at `x` with the Lagrange form over the `degree + 1`
+    /// values closest to `x`. For equally spaced points the Lagrange basis polynomials
+    /// are products of binomial coefficients, so everything stays in integers.
+    pub fn at(&self, x: i128) -> Result<i128, ExtrapolationError> {
+        let points = self.degree + 1;
+        let first = if x < 0 { 0 } else { self.values.len() - points };
+        let t = x - first as i128;
 
-        while let Some(next_sequence) = current_sequence {
-            sequence_history.push(next_sequence.clone());
-            if let Some(new_sequence) = calculate_sequence(next_sequence, |a, b| a - b) {
-                current_sequence = Some(new_sequence);
-            } else {
-                current_sequence = None;
+        let mut result: i128 = 0;
+        for j in 0..points {
+            // L_j(t) = (-1)^(d - j) * C(t, j) * C(t - j - 1, d - j)
+            let mut basis = binomial(t, j)
+                .and_then(|a| Some((a, binomial(t - j as i128 - 1, self.degree - j)?)))
+                .and_then(|(a, b)| a.checked_mul(b))
+                .ok_or(ExtrapolationError::Overflow)?;
+            if (self.degree - j) % 2 == 1 {
+                basis = -basis;
             }
+            let term = basis
+                .checked_mul(self.values[first + j])
+                .ok_or(ExtrapolationError::Overflow)?;
+            result = result
+                .checked_add(term)
+                .ok_or(ExtrapolationError::Overflow)?;
         }
+        Ok(result)
+    }
 
-        for history in sequence_history.iter().rev() {
-            sum += history.first().unwrap();
+    /// The value `steps` positions after the last value of the history when going
+    /// forward, or `steps` positions before the first value when going backward.
+    pub fn extrapolate(
+        &self,
+        direction: Direction,
+        steps: usize,
+    ) -> Result<i128, ExtrapolationError> {
+        match direction {
+            Direction::Forward => self.at(self.values.len() as i128 - 1 + steps as i128),
+            Direction::Backward => self.at(-(steps as i128)),
         }
     }
-    sum
 }
 
-pub fn day09() {
-    let input = aoc_2023::read_input("input/day09.txt").expect("Failed to read file");
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+/// Extrapolates a history by `steps` values in the given direction.
+pub fn extrapolate(
+    sequence: &[i64],
+    direction: Direction,
+    steps: usize,
+) -> Result<i128, ExtrapolationError> {
+    Polynomial::fit(sequence)?.extrapolate(direction, steps)
+}
+
+fn sum_of_extrapolations(input: &str, direction: Direction) -> Solution {
+    let sequences = extract_sequences(input)?;
+    let mut sum: i128 = 0;
+
+    for (index, sequence) in sequences.iter().enumerate() {
+        let value = extrapolate(sequence, direction, 1).map_err(|error| match error {
+            ExtrapolationError::Overflow => Error::Overflow,
+            error => Error::Unsolvable(format!("History {}: {error}", index + 1)),
+        })?;
+        sum = sum.checked_add(value).ok_or(Overflow)?;
+    }
+    Ok(sum.into())
+}
 
-    let result_01 = assignment01(&input);
-    let result_02 = assignment02(&input);
+pub fn assignment01(input: &str) -> Solution {
+    sum_of_extrapolations(input, Direction::Forward)
+}
 
-    println!("Day 09 - Assignment 01 solution: {result_01}");
-    println!("Day 09 - Assignment 02 solution: {result_02}");
+pub fn assignment02(input: &str) -> Solution {
+    sum_of_extrapolations(input, Direction::Backward)
+}
+
+pub fn day09(input_file: &str) {
+    let input = crate::read_input(input_file).expect("Failed to read file");
+
+    answer::print_solution(9, 1, &assignment01(&input));
+    answer::print_solution(9, 2, &assignment02(&input));
     println!("\n");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answer::{Answer, Error};
+    use crate::{generate, property};
+
+    #[test]
+    fn polynomial_test() {
+        let polynomial = Polynomial::fit(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(polynomial.degree, 3);
+        assert_eq!(polynomial.extrapolate(Direction::Forward, 1), Ok(68));
+        assert_eq!(polynomial.extrapolate(Direction::Backward, 1), Ok(5));
+
+        // x^2 + 1
+        let polynomial = Polynomial::fit(&[1, 2, 5, 10]).unwrap();
+        assert_eq!(polynomial.degree, 2);
+        assert_eq!(polynomial.extrapolate(Direction::Forward, 3), Ok(37));
+        assert_eq!(polynomial.extrapolate(Direction::Backward, 4), Ok(17));
+        assert_eq!(polynomial.at(1), Ok(2));
+
+        let polynomial = Polynomial::fit(&[7, 7, 7]).unwrap();
+        assert_eq!(polynomial.degree, 0);
+        assert_eq!(polynomial.extrapolate(Direction::Forward, 1000), Ok(7));
+
+        assert_eq!(Polynomial::fit(&[]), Err(ExtrapolationError::Empty));
+    }
+
+    #[test]
+    fn polynomial_overflow_test() {
+        // x^5
+        let polynomial = Polynomial::fit(&[0, 1, 32, 243, 1024, 3125, 7776]).unwrap();
+        assert_eq!(polynomial.degree, 5);
+        assert_eq!(polynomial.extrapolate(Direction::Forward, 1), Ok(16807));
+        assert_eq!(
+            polynomial.extrapolate(Direction::Forward, 10_000_000_000),
+            Err(ExtrapolationError::Overflow)
+        );
+
+        // the results don't have to fit into an i64
+        let polynomial = Polynomial::fit(&[i64::MIN + 1, 0, i64::MAX]).unwrap();
+        assert_eq!(polynomial.degree, 1);
+        assert_eq!(
+            polynomial.extrapolate(Direction::Forward, 1),
+            Ok(2 * i64::MAX as i128)
+        );
+    }
+
+    #[test]
+    fn difference_table_test() {
+        let table = DifferenceTable::new(&[1, 3, 6, 10, 15, 21]).unwrap();
+        assert!(table.is_polynomial());
+        assert_eq!(table.degree(), Some(2));
+        assert_eq!(
+            table.to_string(),
+            "   1   3   6  10  15  21
+     2   3   4   5   6
+       1   1   1   1
+         0   0   0
+"
+        );
+
+        let table = DifferenceTable::new(&[0, 0]).unwrap();
+        assert_eq!(table.degree(), Some(0));
+        assert_eq!(table.levels.len(), 1);
+
+        // a single value is a constant, whether it is zero or not
+        let table = DifferenceTable::new(&[3]).unwrap();
+        assert!(table.is_polynomial());
+        assert_eq!(table.degree(), Some(0));
+        assert_eq!(
+            Polynomial::fit(&[3]).map(|polynomial| polynomial.degree()),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn not_polynomial_test() {
+        // 2^x needs as many differences as there are values
+        let table = DifferenceTable::new(&[1, 2, 4, 8, 16]).unwrap();
+        assert!(!table.is_polynomial());
+        assert_eq!(table.degree(), None);
+        assert_eq!(table.levels.last(), Some(&vec![1]));
+        assert_eq!(
+            Polynomial::fit(&[1, 2, 4, 8, 16]),
+            Err(ExtrapolationError::NotPolynomial)
+        );
+
+        let error = Error::Unsolvable(
+            "History 2: the differences of the history never become zero".to_owned(),
+        );
+        assert_eq!(assignment01("1 2 3\n1 2 4 8 16\n"), Err(error.clone()));
+        assert_eq!(assignment02("1 2 3\n1 2 4 8 16\n"), Err(error));
+    }
+
+    #[test]
+    fn extrapolate_test() {
+        use Direction::{Backward, Forward};
+
+        assert_eq!(extrapolate(&[], Forward, 1), Err(ExtrapolationError::Empty));
+        assert_eq!(
+            extrapolate(&[], Backward, 1),
+            Err(ExtrapolationError::Empty)
+        );
 
-    const EXAMPLE_DATA: &'static str = r"0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45";
+        // a single value stays the same in both directions
+        assert_eq!(extrapolate(&[0], Forward, 5), Ok(0));
+        assert_eq!(extrapolate(&[4], Forward, 5), Ok(4));
+        assert_eq!(extrapolate(&[4], Backward, 1), Ok(4));
+        assert_eq!(
+            assignment01(
+                "4
+1 2 3
+"
+            ),
+            Ok(Answer::from(8))
+        );
+
+        assert_eq!(extrapolate(&[-3, -3, -3, -3], Forward, 1), Ok(-3));
+        assert_eq!(extrapolate(&[-3, -3, -3, -3], Backward, 7), Ok(-3));
+
+        assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15], Forward, 0), Ok(15));
+        assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15], Backward, 0), Ok(0));
+        assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15], Forward, 2), Ok(21));
+        assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15], Backward, 2), Ok(-6));
+    }
+
+    /// The next value with the differences of the differences, recursively.
+    fn naive_next(values: &[i64]) -> i64 {
+        if values.iter().all(|value| *value == 0) {
+            return 0;
+        }
+        let differences: Vec<i64> = values.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        values[values.len() - 1] + naive_next(&differences)
+    }
+
+    fn naive_histories(input: &str) -> Vec<Vec<i64>> {
+        input
+            .lines()
+            .map(|line| {
+                line.split(' ')
+                    .map(|value| value.parse().unwrap())
+                    .collect()
+            })
+            .collect()
+    }
 
     #[test]
-    fn assignment01_test() {
-        assert_eq!(assignment01(&EXAMPLE_DATA.to_owned()), 114);
+    fn round_trip_test() {
+        property::check(100, generate::day09, |input| {
+            assert_eq!(extract_sequences(input), Ok(naive_histories(input)));
+        });
     }
 
     #[test]
-    fn assignment02_test() {
-        assert_eq!(assignment02(&EXAMPLE_DATA.to_owned()), 2);
+    fn reference_test() {
+        property::check(100, generate::day09, |input| {
+            let histories = naive_histories(input);
+            let forward: i64 = histories.iter().map(|values| naive_next(values)).sum();
+            let backward: i64 = histories
+                .iter()
+                .map(|values| naive_next(&values.iter().rev().copied().collect::<Vec<i64>>()))
+                .sum();
+            assert_eq!(assignment01(input), Ok(Answer::from(forward)));
+            assert_eq!(assignment02(input), Ok(Answer::from(backward)));
+        });
     }
 }
@@ -0,0 +1,306 @@
+//! Generators for synthetic but valid puzzle inputs.
+//!
+//! The meaning of `size` depends on the day, e.g. the number of lines, or the
+//! width and height of the schematic for day 3. The same seed and size always
+//! give the same input.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::random::Rng;
+
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// The generator of every day, with a default size similar to the real puzzle input.
+pub const GENERATORS: &[(&str, Generator, usize)] = &[
+    ("day01", day01, 1000),
+    ("day02", day02, 100),
+    ("day03", day03, 140),
+    ("day04", day04, 200),
+    ("day06", day06, 4),
+    ("day07", day07, 1000),
+    ("day08", day08, 750),
+    ("day09", day09, 200),
+];
+
+pub fn find(day: &str) -> Option<(Generator, usize)> {
+    GENERATORS
+        .iter()
+        .find(|(name, _, _)| *name == day)
+        .map(|(_, generator, size)| (*generator, *size))
+}
+
+const DIGIT_NAMES: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// `size` calibration lines of letters, digits and spelled out digits.
+/// Every line contains at least one digit.
+pub fn day01(rng: &mut Rng, size: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..size {
+        let mut line = String::new();
+        for _ in 0..rng.range(1..8) {
+            match rng.below(3) {
+                0 => line.push(char::from(b'0' + rng.range(1..10) as u8)),
+                1 => line.push_str(DIGIT_NAMES[rng.range(0..DIGIT_NAMES.len())]),
+                _ => line.push(char::from(b'a' + rng.below(26) as u8)),
+            }
+        }
+        if !line.chars().any(|c| c.is_ascii_digit()) {
+            let position = rng.range(0..line.len() + 1);
+            line.insert(position, char::from(b'0' + rng.range(1..10) as u8));
+        }
+        writeln!(text, "{line}").unwrap();
+    }
+    text
+}
+
+/// `size` games with up to six sets of red, green and blue cubes.
+pub fn day02(rng: &mut Rng, size: usize) -> String {
+    let mut text = String::new();
+    for id in 1..=size {
+        let sets: Vec<String> = (0..rng.range(1..7))
+            .map(|_| {
+                let mut colors = ["red", "green", "blue"];
+                rng.shuffle(&mut colors);
+                let shown = rng.range(1..4);
+                colors[..shown]
+                    .iter()
+                    .map(|color| format!("{} {color}", rng.range(1..21)))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .collect();
+        writeln!(text, "Game {id}: {}", sets.join("; ")).unwrap();
+    }
+    text
+}
+
+/// A `size` by `size` engine schematic with numbers of up to three digits and symbols.
+pub fn day03(rng: &mut Rng, size: usize) -> String {
+    const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+    let mut text = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        let mut row: Vec<u8> = Vec::with_capacity(size);
+        while row.len() < size {
+            let digits = rng.range(1..4);
+            if rng.chance(1, 4) && row.len() + digits <= size {
+                row.push(b'1' + rng.below(9) as u8);
+                for _ in 1..digits {
+                    row.push(b'0' + rng.below(10) as u8);
+                }
+                // numbers must not run into each other
+                if row.len() < size {
+                    row.push(b'.');
+                }
+            } else if rng.chance(1, 8) {
+                row.push(*rng.pick(SYMBOLS));
+            } else {
+                row.push(b'.');
+            }
+        }
+        text.push_str(std::str::from_utf8(&row).unwrap());
+        text.push('\n');
+    }
+    text
+}
+
+/// `size` scratchcards with 5 winning and 8 drawn numbers.
+/// On average less than one number matches, so the copies of part 2 stay finite.
+pub fn day04(rng: &mut Rng, size: usize) -> String {
+    let width = size.to_string().len();
+    let mut text = String::new();
+    for id in 1..=size {
+        let mut numbers: Vec<u32> = (1..100).collect();
+        rng.shuffle(&mut numbers);
+        let winning = &numbers[..5];
+        let matches = if rng.chance(1, 2) { 0 } else { rng.range(1..3) };
+        let mut drawn: Vec<u32> = winning[..matches].to_vec();
+        drawn.extend(&numbers[5..13 - matches]);
+        rng.shuffle(&mut drawn);
+
+        let format = |numbers: &[u32]| {
+            numbers
+                .iter()
+                .map(|number| format!("{number:>2}"))
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        writeln!(
+            text,
+            "Card {id:>width$}: {} | {}",
+            format(winning),
+            format(&drawn)
+        )
+        .unwrap();
+    }
+    text
+}
+
+/// A race sheet with `size` races. The record of every race can be beaten.
+/// Part 2 joins all columns, so it only works for a few races.
+pub fn day06(rng: &mut Rng, size: usize) -> String {
+    let races: Vec<(u64, u64)> = (0..size)
+        .map(|_| {
+            let time = rng.range(2..100) as u64;
+            let hold = rng.range(1..time as usize) as u64;
+            (time, hold * (time - hold) - 1)
+        })
+        .collect();
+
+    let mut times = String::from("Time:    ");
+    let mut records = String::from("Distance:");
+    for (time, record) in races {
+        let width = record.to_string().len().max(time.to_string().len()) + 1;
+        write!(times, "{time:>width$}").unwrap();
+        write!(records, "{record:>width$}").unwrap();
+    }
+    format!("{times}\n{records}\n")
+}
+
+/// `size` hands with bids. The hands are distinct as long as there are enough of them.
+pub fn day07(rng: &mut Rng, size: usize) -> String {
+    const CARDS: &[u8] = b"AKQJT98765432";
+    const DISTINCT_HANDS: usize = 13 * 13 * 13 * 13 * 13;
+    let mut seen = HashSet::new();
+    let mut text = String::new();
+    for _ in 0..size {
+        let hand = loop {
+            let hand: String = (0..5).map(|_| char::from(*rng.pick(CARDS))).collect();
+            if seen.len() >= DISTINCT_HANDS || seen.insert(hand.clone()) {
+                break hand;
+            }
+        };
+        writeln!(text, "{hand} {}", rng.range(1..1001)).unwrap();
+    }
+    text
+}
+
+/// Node names of the same length, which never end in `A` or `Z`.
+fn node_name(mut index: usize, width: usize) -> String {
+    const LETTERS: &[u8] = b"BCDEFGHIJKLMNOPQRSTUVWXY";
+    let mut name = vec![b'B'; width];
+    for letter in name.iter_mut().rev() {
+        *letter = LETTERS[index % LETTERS.len()];
+        index /= LETTERS.len();
+    }
+    String::from_utf8(name).unwrap()
+}
+
+/// A network of at least `size` nodes, built like the puzzle input: every start
+/// node `..A` leads into a loop with a single goal `..Z`, whose length is a multiple
+/// of the number of directions. `AAA` and `ZZZ` are the start and goal of the first loop.
+/// All other edges point to random nodes.
+pub fn day08(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let directions: Vec<char> = (0..rng.range(1..size.min(9)))
+        .map(|_| if rng.chance(1, 2) { 'L' } else { 'R' })
+        .collect();
+    let period = directions.len();
+    let ghosts = rng.range(1..(size / (period + 1)).clamp(1, 6) + 1);
+
+    let mut width = 3;
+    while 24usize.pow(width as u32) < size {
+        width += 1;
+    }
+    let mut names: Vec<String> = vec![];
+    let mut next_name = || {
+        names.push(node_name(names.len(), width));
+        names.last().unwrap().clone()
+    };
+
+    // every loop takes at least `period` nodes, the spare ones make loops longer or are fillers
+    let mut spare = size - ghosts * (period + 1);
+    let mut paths: Vec<Vec<String>> = vec![];
+    for ghost in 0..ghosts {
+        let (start, goal) = if ghost == 0 {
+            ("AAA".to_owned(), "ZZZ".to_owned())
+        } else {
+            let prefix = format!("{ghost:0>w$}", w = width - 1);
+            (format!("{prefix}A"), format!("{prefix}Z"))
+        };
+        let extra = rng.range(0..spare / period + 1);
+        spare -= extra * period;
+
+        let mut path = vec![start];
+        for _ in 1..(extra + 1) * period {
+            path.push(next_name());
+        }
+        path.push(goal);
+        paths.push(path);
+    }
+    let fillers: Vec<String> = (0..spare).map(|_| next_name()).collect();
+    let all: Vec<&String> = paths.iter().flatten().chain(&fillers).collect();
+
+    let mut lines: Vec<String> = vec![];
+    for path in &paths {
+        for (step, node) in path.iter().enumerate() {
+            // the goal continues with the first node of the loop
+            let next = path.get(step + 1).unwrap_or(&path[1]);
+            let other = *rng.pick(&all);
+            let (left, right) = match directions[step % period] {
+                'L' => (next, other),
+                _ => (other, next),
+            };
+            lines.push(format!("{node} = ({left}, {right})"));
+        }
+    }
+    for node in &fillers {
+        let (left, right) = (*rng.pick(&all), *rng.pick(&all));
+        lines.push(format!("{node} = ({left}, {right})"));
+    }
+    rng.shuffle(&mut lines);
+
+    let directions: String = directions.into_iter().collect();
+    format!("{directions}\n\n{}\n", lines.join("\n"))
+}
+
+/// `size` histories, each the values of a random polynomial with integer values.
+pub fn day09(rng: &mut Rng, size: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..size {
+        let length = rng.range(2..22);
+        let degree = rng.range(0..(length - 1).min(7));
+        // Newton form, sum of c_k * C(x, k), which is an integer for every integer x
+        let coefficients: Vec<i64> = (0..=degree).map(|_| rng.signed(-9..=9)).collect();
+        let values: Vec<String> = (0..length as i64)
+            .map(|x| {
+                let mut binomial = 1;
+                let mut value = 0;
+                for (k, coefficient) in coefficients.iter().enumerate() {
+                    value += coefficient * binomial;
+                    binomial = binomial * (x - k as i64) / (k as i64 + 1);
+                }
+                value.to_string()
+            })
+            .collect();
+        writeln!(text, "{}", values.join(" ")).unwrap();
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_test() {
+        for (_, generate, _) in GENERATORS {
+            let a = generate(&mut Rng::new(7), 10);
+            let b = generate(&mut Rng::new(7), 10);
+            assert_eq!(a, b);
+            assert!(!a.is_empty());
+        }
+    }
+
+    #[test]
+    fn day08_test() {
+        for size in [1, 2, 5, 100] {
+            let text = day08(&mut Rng::new(size as u64), size);
+            let nodes = text.lines().skip(2).count();
+            assert_eq!(nodes, size.max(2));
+            assert!(text.contains("\nAAA = ") && text.contains("\nZZZ = "));
+        }
+    }
+}
@@ -1,3 +1,13 @@
+pub mod answer;
+pub mod days;
+pub mod generate;
+pub mod math;
+pub mod parser;
+#[cfg(test)]
+mod property;
+pub mod random;
+pub mod ranges;
+
 pub fn read_input(input_file: &str) -> Result<String, std::io::Error> {
     std::fs::read_to_string(input_file)
 }
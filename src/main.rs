@@ -1,14 +1,118 @@
-mod days;
+use std::io::Write;
+
+use aoc_2023::days;
+use aoc_2023::days::day02::{Bag, Palette, PUZZLE_BAG};
+use aoc_2023::generate;
+use aoc_2023::random::Rng;
+
+const USAGE: &str = "Usage: aoc_2023 [dayXX] [--input input/dayXX.txt] [--bag red=12,green=13,blue=14] [--dot network.dot] [--visualize]
+       aoc_2023 generate dayXX [--size N] [--seed N] [--output file]";
+
+/// Prints the usage for a wrong command line and exits.
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+/// Writes a synthetic input for a day to the output file or stdout.
+fn generate(mut args: impl Iterator<Item = String>) {
+    let day = args.next().unwrap_or_else(|| usage());
+    let (generator, mut size) = generate::find(&day).unwrap_or_else(|| usage());
+    let mut seed = 0;
+    let mut output: Option<String> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => {
+                size = args
+                    .next()
+                    .and_then(|size| size.parse().ok())
+                    .unwrap_or_else(|| usage());
+            }
+            "--seed" => {
+                seed = args
+                    .next()
+                    .and_then(|seed| seed.parse().ok())
+                    .unwrap_or_else(|| usage());
+            }
+            "--output" => {
+                output = Some(args.next().unwrap_or_else(|| usage()));
+            }
+            _ => usage(),
+        }
+    }
+
+    let input = generator(&mut Rng::new(seed), size);
+    let written = match &output {
+        Some(file) => std::fs::write(file, input),
+        None => std::io::stdout().write_all(input.as_bytes()),
+    };
+    if let Err(error) = written {
+        let target = output.as_deref().unwrap_or("stdout");
+        eprintln!("Failed to write the input to {target}: {error}");
+        std::process::exit(1);
+    }
+}
 
 fn main() {
-    // days::day01::day01();
-    // days::day02::day02();
-    // days::day03::day03();
-    // days::day04::day04();
-    // days::day05::day05();
-    // days::day06::day06();
-    // days::day06::day06();
-    // days::day07::day07();
-    // days::day08::day08();
-    days::day09::day09();
+    let mut day: Option<String> = None;
+    let mut input_file: Option<String> = None;
+    let mut bag = PUZZLE_BAG.to_owned();
+    let mut dot_file: Option<String> = None;
+    let mut visualize = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "generate" if day.is_none() => return generate(args),
+            "--input" => {
+                input_file = Some(args.next().unwrap_or_else(|| usage()));
+            }
+            "--bag" => {
+                bag = args.next().unwrap_or_else(|| usage());
+            }
+            "--dot" => {
+                dot_file = Some(args.next().unwrap_or_else(|| usage()));
+            }
+            "--visualize" => visualize = true,
+            _ if day.is_none() => day = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    // the bag is checked up front, so a typo isn't reported as a failed assignment
+    if let Err(message) = Bag::parse(&bag, &mut Palette::default()) {
+        eprintln!("Invalid bag '{bag}': {message}");
+        std::process::exit(2);
+    }
+
+    let input = |day: &str| {
+        input_file
+            .clone()
+            .unwrap_or_else(|| format!("input/{day}.txt"))
+    };
+
+    match day.as_deref() {
+        Some("day01") => days::day01::day01(&input("day01")),
+        Some("day02") => days::day02::day02(&input("day02"), &bag),
+        Some("day03") => days::day03::day03(&input("day03"), visualize),
+        Some("day04") => days::day04::day04(&input("day04")),
+        Some("day06") => days::day06::day06(&input("day06")),
+        Some("day07") => days::day07::day07(&input("day07")),
+        Some("day08") => days::day08::day08(&input("day08"), dot_file.as_deref()),
+        Some("day09") => days::day09::day09(&input("day09")),
+        // a single input file can't be used for all days
+        None if input_file.is_some() => usage(),
+        None => {
+            days::day01::day01(&input("day01"));
+            days::day02::day02(&input("day02"), &bag);
+            days::day03::day03(&input("day03"), visualize);
+            days::day04::day04(&input("day04"));
+            days::day06::day06(&input("day06"));
+            days::day07::day07(&input("day07"));
+            days::day08::day08(&input("day08"), dot_file.as_deref());
+            days::day09::day09(&input("day09"));
+        }
+        Some(_) => usage(),
+    }
 }
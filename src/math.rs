@@ -0,0 +1,247 @@
+//! Number theory helpers shared by the days.
+
+use std::fmt;
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, or `None` if it doesn't fit into an u64.
+/// The LCM with zero is zero.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The GCD of all values, zero if there are none.
+pub fn gcd_all(values: impl IntoIterator<Item = u64>) -> u64 {
+    values.into_iter().fold(0, gcd)
+}
+
+/// The LCM of all values, one if there are none.
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b) >= 0` and `a * x + b * y = g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrtError {
+    /// Two congruences contradict each other, e.g. `x = 1 mod 2` and `x = 2 mod 4`.
+    Incompatible,
+    /// The combined modulus doesn't fit into an u64.
+    Overflow,
+    /// A modulus is zero.
+    ZeroModulus,
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrtError::Incompatible => write!(f, "the congruences are incompatible"),
+            CrtError::Overflow => write!(f, "the combined modulus is too large"),
+            CrtError::ZeroModulus => write!(f, "a modulus is zero"),
+        }
+    }
+}
+
+impl std::error::Error for CrtError {}
+
+/// Solves the system `x = residue mod modulus` for all given `(residue, modulus)` pairs.
+/// The moduli don't have to be coprime. Returns the smallest non-negative solution
+/// together with the LCM of the moduli, so every solution is `x + k * lcm`.
+pub fn crt(congruences: impl IntoIterator<Item = (u64, u64)>) -> Result<(u64, u64), CrtError> {
+    let mut result: (u64, u64) = (0, 1);
+    for (residue, modulus) in congruences {
+        if modulus == 0 {
+            return Err(CrtError::ZeroModulus);
+        }
+        let (x, m) = (result.0 as i128, result.1 as i128);
+        let (r, n) = ((residue % modulus) as i128, modulus as i128);
+
+        // x + m * t = r (mod n)  <=>  m * t = r - x (mod n)
+        let (g, inverse, _) = extended_gcd(m, n);
+        if (r - x) % g != 0 {
+            return Err(CrtError::Incompatible);
+        }
+        let reduced = n / g;
+        let combined = m.checked_mul(reduced).ok_or(CrtError::Overflow)?;
+        let combined = u64::try_from(combined).map_err(|_| CrtError::Overflow)?;
+
+        // both factors are below 2^64, so their product fits into an u128
+        let difference = ((r - x) / g).rem_euclid(reduced) as u128;
+        let inverse = inverse.rem_euclid(reduced) as u128;
+        let t = (difference * inverse % reduced as u128) as i128;
+        // x < m and t < n / g, so this is smaller than the combined modulus
+        result = ((x + m * t) as u64, combined);
+    }
+    Ok(result)
+}
+
+/// The largest `r` with `r * r <= n`.
+pub fn isqrt_u64(n: u64) -> u64 {
+    isqrt_u128(n as u128) as u64
+}
+
+/// The largest `r` with `r * r <= n`, with Newton's method.
+pub fn isqrt_u128(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // start above the root, from there the iteration decreases monotonically
+    let mut x = 1u128 << (n.ilog2() / 2 + 1);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+/// The binomial coefficient `C(n, k)`, also defined for negative `n`.
+/// Returns `None` if the result doesn't fit into an i128.
+pub fn binomial(n: i128, k: usize) -> Option<i128> {
+    let mut result: i128 = 1;
+    for i in 0..k as i128 {
+        // C(n, i) * (n - i) is always divisible by (i + 1)
+        result = result.checked_mul(n - i)? / (i + 1);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_lcm_test() {
+        for a in 0..=60u64 {
+            for b in 0..=60u64 {
+                let g = gcd(a, b);
+                let expected = (1..=a.max(b))
+                    .filter(|d| a % d == 0 && b % d == 0)
+                    .max()
+                    .unwrap_or(0);
+                assert_eq!(g, expected, "gcd({a}, {b})");
+
+                let l = lcm(a, b).unwrap();
+                let expected = if a == 0 || b == 0 {
+                    0
+                } else {
+                    (1..=a * b).find(|m| m % a == 0 && m % b == 0).unwrap()
+                };
+                assert_eq!(l, expected, "lcm({a}, {b})");
+            }
+        }
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(lcm(u64::MAX, u64::MAX), Some(u64::MAX));
+    }
+
+    #[test]
+    fn gcd_lcm_all_test() {
+        assert_eq!(gcd_all([]), 0);
+        assert_eq!(gcd_all([12, 18, 30]), 6);
+        assert_eq!(lcm_all([]), Some(1));
+        assert_eq!(lcm_all([2, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all([2, 0, 4]), Some(0));
+        assert_eq!(lcm_all([1 << 40, 3 << 30, 5 << 30]), Some(15 << 40));
+        assert_eq!(lcm_all([u64::MAX, 2]), None);
+    }
+
+    #[test]
+    fn extended_gcd_test() {
+        for a in -40..=40i128 {
+            for b in -40..=40i128 {
+                let (g, x, y) = extended_gcd(a, b);
+                assert_eq!(
+                    g,
+                    gcd(a.unsigned_abs() as u64, b.unsigned_abs() as u64) as i128
+                );
+                assert_eq!(a * x + b * y, g, "extended_gcd({a}, {b})");
+            }
+        }
+    }
+
+    #[test]
+    fn crt_test() {
+        // every pair of congruences with small moduli, against a search
+        for m in 1..=12u64 {
+            for n in 1..=12u64 {
+                for a in 0..m {
+                    for b in 0..n {
+                        let expected = (0..m * n).find(|x| x % m == a && x % n == b);
+                        let result = crt([(a, m), (b, n)]);
+                        match expected {
+                            Some(x) => assert_eq!(result, Ok((x, lcm(m, n).unwrap()))),
+                            None => assert_eq!(result, Err(CrtError::Incompatible)),
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(crt([]), Ok((0, 1)));
+        assert_eq!(crt([(7, 3)]), Ok((1, 3)));
+        assert_eq!(crt([(1, 0)]), Err(CrtError::ZeroModulus));
+        assert_eq!(crt([(1, 1 << 62), (0, 3), (0, 5)]), Err(CrtError::Overflow));
+        let (m, n) = ((1u64 << 32) - 1, 1u64 << 32);
+        let (x, combined) = crt([(m - 1, m), (n - 1, n)]).unwrap();
+        assert_eq!((x % m, x % n, combined), (m - 1, n - 1, m * n));
+    }
+
+    #[test]
+    fn isqrt_test() {
+        for n in 0..=100_000u64 {
+            let r = isqrt_u64(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "isqrt({n})");
+        }
+        for r in [1u64 << 20, (1 << 32) - 1, 3_037_000_499] {
+            assert_eq!(isqrt_u64(r * r), r);
+            assert_eq!(isqrt_u64(r * r - 1), r - 1);
+        }
+        assert_eq!(isqrt_u64(u64::MAX), (1 << 32) - 1);
+        assert_eq!(isqrt_u128(u128::MAX), (1 << 64) - 1);
+        assert_eq!(isqrt_u128(u128::MAX - 1), (1 << 64) - 1);
+        let r = u64::MAX as u128;
+        assert_eq!(isqrt_u128(r * r), r);
+        assert_eq!(isqrt_u128(r * r - 1), r - 1);
+    }
+
+    #[test]
+    fn binomial_test() {
+        // Pascal's rule C(n, k) = C(n - 1, k - 1) + C(n - 1, k), for negative n as well
+        for n in -30..=60i128 {
+            assert_eq!(binomial(n, 0), Some(1));
+            for k in 1..=20 {
+                let expected = binomial(n - 1, k - 1).unwrap() + binomial(n - 1, k).unwrap();
+                assert_eq!(binomial(n, k), Some(expected), "C({n}, {k})");
+            }
+        }
+        for n in 0..20 {
+            assert_eq!(binomial(n, n as usize + 1), Some(0));
+        }
+        assert_eq!(binomial(-1, 7), Some(-1));
+        assert_eq!(binomial(200, 100), None);
+    }
+}
@@ -0,0 +1,476 @@
+//! A small set of parser combinators working directly on the puzzle input.
+//!
+//! Parsers borrow from the original text instead of copying it and report
+//! errors with the line and column where parsing failed.
+
+use std::fmt;
+use std::num::{IntErrorKind, ParseIntError};
+use std::str::FromStr;
+
+/// The not yet parsed part of the input, together with its offset into the original text.
+/// The current line is tracked while advancing, so errors are cheap to create even
+/// though most of them are thrown away when a parser backtracks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Input<'a> {
+    source: &'a str,
+    offset: usize,
+    line: usize,
+    line_start: usize,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(source: &'a str) -> Input<'a> {
+        Input {
+            source,
+            offset: 0,
+            line: 1,
+            line_start: 0,
+        }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.source[self.offset..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offset >= self.source.len()
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Line and column (both starting at 1) of the current position.
+    pub fn position(&self) -> (usize, usize) {
+        let column = self.source[self.line_start..self.offset].chars().count() + 1;
+        (self.line, column)
+    }
+
+    fn advance(&self, len: usize) -> Input<'a> {
+        let skipped = &self.source[self.offset..self.offset + len];
+        let mut next = Input {
+            offset: self.offset + len,
+            ..*self
+        };
+        if let Some(last) = skipped.rfind('\n') {
+            next.line += skipped.matches('\n').count();
+            next.line_start = self.offset + last + 1;
+        }
+        next
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        let (line, column) = self.position();
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<'a, T> = Result<(Input<'a>, T), ParseError>;
+
+pub trait Parser<'a, T> {
+    fn parse(&self, input: Input<'a>) -> ParseResult<'a, T>;
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(Input<'a>) -> ParseResult<'a, T>,
+{
+    fn parse(&self, input: Input<'a>) -> ParseResult<'a, T> {
+        self(input)
+    }
+}
+
+/// Runs the parser on the whole text. Only trailing whitespace may be left over.
+pub fn parse_all<'a, T>(parser: impl Parser<'a, T>, text: &'a str) -> Result<T, ParseError> {
+    let (rest, value) = parser.parse(Input::new(text))?;
+    let trimmed = rest.rest().trim_start();
+    let rest = rest.advance(rest.rest().len() - trimmed.len());
+    if !rest.is_empty() {
+        return Err(rest.error("Unexpected trailing input"));
+    }
+    Ok(value)
+}
+
+/// Matches exactly the given text.
+pub fn tag<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        if input.rest().starts_with(expected) {
+            Ok((
+                input.advance(expected.len()),
+                &input.rest()[..expected.len()],
+            ))
+        } else {
+            Err(input.error(format!("Expected '{expected}'")))
+        }
+    }
+}
+
+fn take_while<'a>(input: Input<'a>, predicate: impl Fn(char) -> bool) -> (Input<'a>, &'a str) {
+    let rest = input.rest();
+    let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+    (input.advance(len), &rest[..len])
+}
+
+/// Skips any amount of spaces and tabs, but no line breaks.
+pub fn space0<'a>() -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| Ok(take_while(input, |c| c == ' ' || c == '\t'))
+}
+
+/// Skips at least one space or tab.
+pub fn space1<'a>() -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| match take_while(input, |c| c == ' ' || c == '\t') {
+        (_, "") => Err(input.error("Expected whitespace")),
+        result => Ok(result),
+    }
+}
+
+/// A non-empty run of characters matching the predicate.
+pub fn take_while1<'a>(
+    predicate: impl Fn(char) -> bool,
+    expected: &'static str,
+) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| match take_while(input, &predicate) {
+        (_, "") => Err(input.error(format!("Expected {expected}"))),
+        result => Ok(result),
+    }
+}
+
+/// A non-empty run of letters and digits, e.g. a color or a node name.
+pub fn word<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(char::is_alphanumeric, "a word")
+}
+
+/// A decimal integer with an optional leading minus sign.
+pub fn number<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let sign = usize::from(input.rest().starts_with('-'));
+        let (_, digits) = take_while(input.advance(sign), |c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return Err(input.error("Expected a number"));
+        }
+        let len = sign + digits.len();
+        match input.rest()[..len].parse::<T>() {
+            Ok(value) => Ok((input.advance(len), value)),
+            Err(_) => Err(input.error(format!("Invalid number '{}'", &input.rest()[..len]))),
+        }
+    }
+}
+
+/// A whole token up to the next whitespace as an integer of type `T`, so the sign
+/// and the width of `T` are checked as well. Unlike `number`, a typo like `1O` is
+/// reported as an invalid number instead of stopping after the `1`.
+pub fn integer<'a, T: FromStr<Err = ParseIntError>>() -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let (next, token) = take_while(input, |c| !c.is_whitespace());
+        if token.is_empty() {
+            return Err(input.error("Expected a number"));
+        }
+        let error = match token.parse::<T>() {
+            Ok(value) => return Ok((next, value)),
+            Err(error) => error,
+        };
+        let message = match error.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => format!(
+                "Number '{token}' doesn't fit into {}",
+                std::any::type_name::<T>()
+            ),
+            _ if token.starts_with('-') && token[1..].parse::<T>().is_ok() => {
+                format!("Expected an unsigned number, found '{token}'")
+            }
+            _ => format!("Invalid number '{token}'"),
+        };
+        Err(input.error(message))
+    }
+}
+
+/// The current input, without consuming anything.
+/// Useful to report an error at this position once parsing has finished.
+pub fn position<'a>() -> impl Parser<'a, Input<'a>> {
+    move |input: Input<'a>| Ok((input, input))
+}
+
+/// A line break, or the end of the input.
+pub fn line_ending<'a>() -> impl Parser<'a, ()> {
+    move |input: Input<'a>| {
+        if input.is_empty() {
+            Ok((input, ()))
+        } else if input.rest().starts_with("\r\n") {
+            Ok((input.advance(2), ()))
+        } else if input.rest().starts_with('\n') {
+            Ok((input.advance(1), ()))
+        } else {
+            Err(input.error("Expected end of line"))
+        }
+    }
+}
+
+pub fn map<'a, A, B>(parser: impl Parser<'a, A>, f: impl Fn(A) -> B) -> impl Parser<'a, B> {
+    move |input: Input<'a>| {
+        let (input, value) = parser.parse(input)?;
+        Ok((input, f(value)))
+    }
+}
+
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |input: Input<'a>| match parser.parse(input) {
+        Ok((input, value)) => Ok((input, Some(value))),
+        Err(_) => Ok((input, None)),
+    }
+}
+
+pub fn pair<'a, A, B>(
+    first: impl Parser<'a, A>,
+    second: impl Parser<'a, B>,
+) -> impl Parser<'a, (A, B)> {
+    move |input: Input<'a>| {
+        let (input, a) = first.parse(input)?;
+        let (input, b) = second.parse(input)?;
+        Ok((input, (a, b)))
+    }
+}
+
+/// Parses `first` and `second`, but only keeps the second value.
+pub fn preceded<'a, A, B>(
+    first: impl Parser<'a, A>,
+    second: impl Parser<'a, B>,
+) -> impl Parser<'a, B> {
+    map(pair(first, second), |(_, b)| b)
+}
+
+/// Parses `first` and `second`, but only keeps the first value.
+pub fn terminated<'a, A, B>(
+    first: impl Parser<'a, A>,
+    second: impl Parser<'a, B>,
+) -> impl Parser<'a, A> {
+    map(pair(first, second), |(a, _)| a)
+}
+
+pub fn delimited<'a, A, B, C>(
+    open: impl Parser<'a, A>,
+    value: impl Parser<'a, B>,
+    close: impl Parser<'a, C>,
+) -> impl Parser<'a, B> {
+    preceded(open, terminated(value, close))
+}
+
+/// One or more items, separated by `separator`.
+pub fn separated_list<'a, S, T>(
+    separator: impl Parser<'a, S>,
+    item: impl Parser<'a, T>,
+) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let (mut input, first) = item.parse(input)?;
+        let mut items = vec![first];
+        while let Ok((next, _)) = separator.parse(input) {
+            match item.parse(next) {
+                Ok((next, value)) => {
+                    items.push(value);
+                    input = next;
+                }
+                Err(_) => break,
+            }
+        }
+        Ok((input, items))
+    }
+}
+
+/// The given parser, followed by optional trailing whitespace and the end of the line.
+pub fn line<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    terminated(parser, pair(space0(), line_ending()))
+}
+
+/// A whole line in the form of `label: body`, e.g. `Time: 7 15 30`.
+pub fn labelled_line<'a, L, T>(
+    label: impl Parser<'a, L>,
+    body: impl Parser<'a, T>,
+) -> impl Parser<'a, (L, T)> {
+    line(pair(terminated(label, pair(tag(":"), space0())), body))
+}
+
+/// A whole line of integers separated by whitespace, e.g. `Time:  7 15 30` with the
+/// label `Some("Time")` or `0 3 -6` without a label. Every token must be a number
+/// of type `T`, a label can only appear where one is expected.
+pub fn numeric_line<'a, T: FromStr<Err = ParseIntError>>(
+    label: Option<&'static str>,
+) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let (mut input, _) = space0().parse(input)?;
+        if let Some(label) = label {
+            match pair(tag(label), tag(":")).parse(input) {
+                Ok((next, _)) => input = next,
+                Err(_) => return Err(input.error(format!("Expected the label '{label}:'"))),
+            }
+        }
+        let mut numbers = vec![];
+        loop {
+            (input, _) = space0().parse(input)?;
+            if let Ok((next, ())) = line_ending().parse(input) {
+                return Ok((next, numbers));
+            }
+            let (_, token) = take_while(input, |c| !c.is_whitespace());
+            if token.ends_with(':') {
+                return Err(input.error(format!("Unexpected label '{token}'")));
+            }
+            let (next, number) = integer().parse(input)?;
+            numbers.push(number);
+            input = next;
+        }
+    }
+}
+
+fn is_blank_line(input: Input<'_>) -> bool {
+    let rest = input.rest();
+    rest.starts_with('\n') || rest.starts_with("\r\n")
+}
+
+/// Consecutive lines parsed by `item`, up to a blank line or the end of the input.
+/// The blank lines after the block are consumed as well.
+pub fn block<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |mut input: Input<'a>| {
+        let mut items = vec![];
+        while !input.is_empty() && !is_blank_line(input) {
+            let (next, value) = item.parse(input)?;
+            if next.offset() == input.offset() {
+                return Err(input.error("Line was not consumed"));
+            }
+            items.push(value);
+            input = next;
+        }
+        while is_blank_line(input) {
+            (input, _) = line_ending().parse(input)?;
+        }
+        Ok((input, items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labelled_line_test() {
+        let parser = labelled_line(tag("Time"), separated_list(space1(), number::<u32>()));
+        let (rest, (label, numbers)) = parser.parse(Input::new("Time:  7 15   30\nrest")).unwrap();
+        assert_eq!(label, "Time");
+        assert_eq!(numbers, vec![7, 15, 30]);
+        assert_eq!(rest.rest(), "rest");
+    }
+
+    #[test]
+    fn block_test() {
+        let text = "LLR\r\n\r\nAAA 1\nBBB -2\n";
+        let directions = block(line(word()));
+        let nodes = block(line(pair(word(), preceded(space1(), number::<i32>()))));
+        let (directions, nodes) = parse_all(pair(directions, nodes), text).unwrap();
+        assert_eq!(directions, vec!["LLR"]);
+        assert_eq!(nodes, vec![("AAA", 1), ("BBB", -2)]);
+    }
+
+    #[test]
+    fn error_position_test() {
+        let parser = block(line(separated_list(space1(), number::<u8>())));
+        let error = parse_all(parser, "1 2 3\n4 5 x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+
+        let error = parse_all(number::<u8>(), "300").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(error.to_string(), "line 1, column 1: Invalid number '300'");
+    }
+
+    #[test]
+    fn position_test() {
+        let input = Input::new("ab\ncd\r\néf\n");
+        assert_eq!(input.position(), (1, 1));
+        assert_eq!(input.advance(2).position(), (1, 3));
+        assert_eq!(input.advance(3).position(), (2, 1));
+        assert_eq!(input.advance(7).advance(2).position(), (3, 2));
+        assert_eq!(input.advance(11).position(), (4, 1));
+    }
+
+    #[test]
+    fn integer_test() {
+        assert_eq!(parse_all(integer::<i8>(), "-128"), Ok(-128));
+        assert_eq!(
+            parse_all(integer::<u64>(), "18446744073709551615"),
+            Ok(u64::MAX)
+        );
+
+        let message = |text, result: Result<u32, ParseError>| {
+            let error = result.unwrap_err();
+            assert_eq!((error.line, error.column), (1, 1), "{text}");
+            error.message
+        };
+        for (text, expected) in [
+            ("1O", "Invalid number '1O'"),
+            ("12,", "Invalid number '12,'"),
+            ("", "Expected a number"),
+            ("-7", "Expected an unsigned number, found '-7'"),
+            ("4294967296", "Number '4294967296' doesn't fit into u32"),
+        ] {
+            assert_eq!(message(text, parse_all(integer(), text)), expected);
+        }
+        let error = parse_all(integer::<i16>(), "-40000").unwrap_err();
+        assert_eq!(error.message, "Number '-40000' doesn't fit into i16");
+    }
+
+    #[test]
+    fn numeric_line_test() {
+        let sheet = pair(
+            numeric_line::<u32>(Some("Time")),
+            numeric_line(Some("Distance")),
+        );
+        let text = "Time:      7  15   30\nDistance:  9  40  200\n";
+        assert_eq!(
+            parse_all(sheet, text),
+            Ok((vec![7, 15, 30], vec![9, 40, 200]))
+        );
+
+        let values = block(numeric_line::<i64>(None));
+        assert_eq!(
+            parse_all(values, "0 3 -6\r\n  1\t2  \n"),
+            Ok(vec![vec![0, 3, -6], vec![1, 2]])
+        );
+
+        // a typo is an error at its position instead of a missing value
+        let error = parse_all(numeric_line::<u32>(Some("Time")), "Time: 7 1O 30").unwrap_err();
+        assert_eq!(
+            (error.column, error.message.as_str()),
+            (9, "Invalid number '1O'")
+        );
+
+        let error = parse_all(numeric_line::<u32>(Some("Time")), "Tim: 7").unwrap_err();
+        assert_eq!(
+            (error.column, error.message.as_str()),
+            (1, "Expected the label 'Time:'")
+        );
+
+        let error = parse_all(numeric_line::<i64>(None), "1 2 Distance: 3").unwrap_err();
+        assert_eq!(
+            (error.column, error.message.as_str()),
+            (5, "Unexpected label 'Distance:'")
+        );
+    }
+}
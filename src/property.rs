@@ -0,0 +1,100 @@
+//! Property based testing: checks a property against many generated values.
+//!
+//! Every case gets its own seed and a size that grows with the number of cases.
+//! When a case fails, smaller sizes with the same seed are tried, so the reported
+//! value is as small as possible. Set `PROPERTY_SEED` to change the seeds of a run,
+//! and additionally `PROPERTY_SIZE` to only run the case with exactly that seed and size.
+
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::random::Rng;
+
+/// The size of the last case.
+pub const MAX_SIZE: usize = 32;
+
+const DEFAULT_SEED: u64 = 2023;
+
+fn from_env<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok()?.parse().ok()
+}
+
+/// Runs `property` for `cases` values created by `generate(rng, size)`.
+/// The property fails by panicking, e.g. with `assert_eq!`.
+pub fn check<T: Debug>(
+    cases: usize,
+    generate: impl Fn(&mut Rng, usize) -> T,
+    property: impl Fn(&T),
+) {
+    let base = from_env("PROPERTY_SEED").unwrap_or(DEFAULT_SEED);
+    if let Some(size) = from_env("PROPERTY_SIZE") {
+        if let Some(message) = failure(&generate, &property, base, size) {
+            shrink(&generate, &property, base, size, message);
+        }
+        return;
+    }
+    for case in 0..cases {
+        let seed = base.wrapping_add(case as u64);
+        let size = 1 + case * MAX_SIZE / cases.max(1);
+        if let Some(message) = failure(&generate, &property, seed, size) {
+            shrink(&generate, &property, seed, size, message);
+        }
+    }
+}
+
+/// Runs a single case and returns the panic message if it fails.
+fn failure<T>(
+    generate: &impl Fn(&mut Rng, usize) -> T,
+    property: &impl Fn(&T),
+    seed: u64,
+    size: usize,
+) -> Option<String> {
+    let value = generate(&mut Rng::new(seed), size);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| property(&value)));
+    let payload = result.err()?;
+    let message = payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_default();
+    Some(message)
+}
+
+/// Finds the smallest size that still fails for the seed and reports it.
+fn shrink<T: Debug>(
+    generate: &impl Fn(&mut Rng, usize) -> T,
+    property: &impl Fn(&T),
+    seed: u64,
+    size: usize,
+    message: String,
+) -> ! {
+    let (size, message) = (1..size)
+        .find_map(|smaller| Some((smaller, failure(generate, property, seed, smaller)?)))
+        .unwrap_or((size, message));
+    let value = generate(&mut Rng::new(seed), size);
+    panic!("Property failed with seed {seed} and size {size}: {message}\nvalue: {value:#?}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_test() {
+        check(
+            50,
+            |rng, size| (0..size).map(|_| rng.below(100)).collect::<Vec<u64>>(),
+            |values| assert!(values.iter().all(|value| *value < 100)),
+        );
+    }
+
+    #[test]
+    fn shrink_test() {
+        let result = panic::catch_unwind(|| {
+            check(50, |_, size| size, |size| assert!(*size < 10, "too large"))
+        });
+        let payload = result.unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.contains("size 10: too large"), "{message}");
+    }
+}
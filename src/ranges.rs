@@ -0,0 +1,282 @@
+//! Sets of integers stored as disjoint half-open ranges.
+
+use std::ops::Range;
+
+/// The integer types a `RangeSet` can hold.
+pub trait Integer: Copy + Ord {
+    /// The number of values from `start` up to, but not including, `end`.
+    fn distance(start: Self, end: Self) -> u64;
+
+    /// Adds `offset`, or `None` if the result doesn't fit.
+    fn checked_shift(self, offset: i64) -> Option<Self>;
+}
+
+impl Integer for i64 {
+    fn distance(start: i64, end: i64) -> u64 {
+        end.abs_diff(start)
+    }
+
+    fn checked_shift(self, offset: i64) -> Option<i64> {
+        self.checked_add(offset)
+    }
+}
+
+impl Integer for u64 {
+    fn distance(start: u64, end: u64) -> u64 {
+        end - start
+    }
+
+    fn checked_shift(self, offset: i64) -> Option<u64> {
+        self.checked_add_signed(offset)
+    }
+}
+
+/// A set of integers. The ranges are kept sorted, non-empty and apart from each
+/// other, so two sets with the same values are always equal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeSet<T = i64> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> RangeSet<T> {
+        RangeSet { ranges: vec![] }
+    }
+}
+
+impl<T: Integer> RangeSet<T> {
+    pub fn new() -> RangeSet<T> {
+        RangeSet::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of integers in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|range| T::distance(range.start, range.end))
+            .sum()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.start <= value)
+    }
+
+    /// Adds a range, merging it with all ranges it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|other| other.end < range.start);
+        let last = self
+            .ranges
+            .partition_point(|other| other.start <= range.end);
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.ranges[first].start);
+            merged.end = merged.end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.insert(range.clone());
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            // the range that ends first can't overlap anything else
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// All values of `self` which are not in `other`.
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut ranges = vec![];
+        let mut j = 0;
+        for range in &self.ranges {
+            let mut start = range.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            for cut in other.ranges[j..]
+                .iter()
+                .take_while(|cut| cut.start < range.end)
+            {
+                if start < cut.start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// Moves every value by `offset`, or `None` if a value leaves the range of `T`.
+    pub fn shift(&self, offset: i64) -> Option<RangeSet<T>> {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|range| Some(range.start.checked_shift(offset)?..range.end.checked_shift(offset)?))
+            .collect::<Option<Vec<Range<T>>>>()?;
+        Some(RangeSet { ranges })
+    }
+
+    /// Splits into the values below `point` and the values from `point` on.
+    pub fn split_at(&self, point: T) -> (RangeSet<T>, RangeSet<T>) {
+        let index = self.ranges.partition_point(|range| range.end <= point);
+        let mut below = self.ranges[..index].to_vec();
+        let mut above = self.ranges[index..].to_vec();
+        if let Some(range) = above.first_mut() {
+            if range.start < point {
+                below.push(range.start..point);
+                range.start = point;
+            }
+        }
+        (RangeSet { ranges: below }, RangeSet { ranges: above })
+    }
+}
+
+impl<T: Integer> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(ranges: I) -> RangeSet<T> {
+        let mut set = RangeSet::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property;
+    use crate::random::Rng;
+    use std::collections::BTreeSet;
+
+    const UNIVERSE: Range<i64> = -30..40;
+
+    fn random_set(rng: &mut Rng, size: usize) -> RangeSet {
+        (0..rng.range(0..size + 1))
+            .map(|_| {
+                let start = rng.signed(-20..=20);
+                start..start + rng.signed(-2..=8)
+            })
+            .collect()
+    }
+
+    fn values(set: &RangeSet) -> BTreeSet<i64> {
+        UNIVERSE.filter(|value| set.contains(*value)).collect()
+    }
+
+    /// The ranges are sorted, non-empty and neither overlap nor touch.
+    fn assert_normalized(set: &RangeSet) {
+        assert!(set.ranges.iter().all(|range| range.start < range.end));
+        assert!(set
+            .ranges
+            .windows(2)
+            .all(|pair| pair[0].end < pair[1].start));
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn insert_test() {
+        let mut set: RangeSet = RangeSet::new();
+        set.insert(5..8);
+        set.insert(0..2);
+        set.insert(3..3);
+        assert_eq!(set.ranges(), &[0..2, 5..8]);
+        set.insert(2..5);
+        assert_eq!(set.ranges(), &[0..8]);
+        set.insert(10..12);
+        set.insert(-4..11);
+        assert_eq!(set.ranges(), &[-4..12]);
+        assert_eq!(set.len(), 16);
+        assert!(set.contains(-4) && set.contains(11) && !set.contains(12));
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn split_and_shift_test() {
+        let set: RangeSet = [0..4, 6..9].into_iter().collect();
+        let (below, above) = set.split_at(2);
+        assert_eq!(
+            (below.ranges(), above.ranges()),
+            (&[0..2][..], &[2..4, 6..9][..])
+        );
+        let (below, above) = set.split_at(5);
+        assert_eq!((below.ranges(), above.ranges()), (&[0..4][..], &[6..9][..]));
+        assert_eq!(set.shift(-10).unwrap().ranges(), &[-10..-6, -4..-1]);
+        assert_eq!(set.shift(i64::MAX), None);
+
+        let set: RangeSet<u64> = [3..5, u64::MAX - 2..u64::MAX].into_iter().collect();
+        assert_eq!(set.len(), 4);
+        assert_eq!(
+            set.shift(-3).unwrap().ranges(),
+            &[0..2, u64::MAX - 5..u64::MAX - 3]
+        );
+        assert_eq!(set.shift(-4), None);
+        assert_eq!(set.shift(1), None);
+    }
+
+    #[test]
+    fn reference_test() {
+        let pairs = |rng: &mut Rng, size: usize| (random_set(rng, size), random_set(rng, size));
+        property::check(200, pairs, |(a, b)| {
+            let (x, y) = (values(a), values(b));
+            for set in [a, b] {
+                assert_normalized(set);
+                assert_eq!(set.len(), values(set).len() as u64);
+            }
+
+            let union = a.union(b);
+            assert_normalized(&union);
+            assert_eq!(values(&union), &x | &y);
+
+            let intersection = a.intersection(b);
+            assert_normalized(&intersection);
+            assert_eq!(values(&intersection), &x & &y);
+
+            let difference = a.difference(b);
+            assert_normalized(&difference);
+            assert_eq!(values(&difference), &x - &y);
+
+            let (below, above) = a.split_at(3);
+            assert_eq!(values(&below), x.range(..3).copied().collect());
+            assert_eq!(values(&above), x.range(3..).copied().collect());
+
+            let shifted = a.shift(2).unwrap();
+            assert_eq!(shifted.shift(-2).as_ref(), Some(a));
+        });
+    }
+}
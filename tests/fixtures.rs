@@ -0,0 +1,129 @@
+//! Runs every assignment against the example inputs in `fixtures/`.
+//!
+//! Every day has a directory `fixtures/dayXX`, with any number of inputs `NAME.txt`.
+//! The expected answers of an input are in `NAME.answers`, one `assignmentXX: answer`
+//! per line. Assignments without an expected answer are not run for that input.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aoc_2023::answer::Solution;
+use aoc_2023::days::{day01, day02, day03, day04, day06, day07, day08, day09};
+
+type Assignment = fn(&str) -> Solution;
+
+const DAYS: &[(&str, [Assignment; 2])] = &[
+    ("day01", [day01::assignment01, day01::assignment02]),
+    (
+        "day02",
+        [
+            |input| day02::assignment01(input, day02::PUZZLE_BAG),
+            day02::assignment02,
+        ],
+    ),
+    ("day03", [day03::assignment01, day03::assignment02]),
+    ("day04", [day04::assignment01, day04::assignment02]),
+    ("day06", [day06::assignment01, day06::assignment02]),
+    ("day07", [day07::assignment01, day07::assignment02]),
+    ("day08", [day08::assignment01, day08::assignment02]),
+    ("day09", [day09::assignment01, day09::assignment02]),
+];
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// All `(input, answers)` file pairs of a day, sorted by name.
+fn find_fixtures(day: &str) -> Vec<(PathBuf, PathBuf)> {
+    let Ok(entries) = fs::read_dir(fixtures_dir().join(day)) else {
+        return vec![];
+    };
+    let mut fixtures: Vec<(PathBuf, PathBuf)> = entries
+        .map(|entry| entry.expect("Failed to read fixture directory").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+        .map(|input| {
+            let answers = input.with_extension("answers");
+            (input, answers)
+        })
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+/// Parses the `assignmentXX: answer` lines into the assignment index and the answer.
+fn parse_answers(text: &str) -> Result<Vec<(usize, String)>, String> {
+    let mut answers = vec![];
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let (assignment, answer) = line
+            .split_once(':')
+            .ok_or_else(|| format!("Expected 'assignmentXX: answer', found '{line}'"))?;
+        let index = match assignment.trim() {
+            "assignment01" => 0,
+            "assignment02" => 1,
+            other => return Err(format!("Unknown assignment '{other}'")),
+        };
+        answers.push((index, answer.trim().to_owned()));
+    }
+    Ok(answers)
+}
+
+#[test]
+fn fixtures_test() {
+    let mut failures = vec![];
+    let mut checked = 0;
+
+    for entry in fs::read_dir(fixtures_dir()).expect("Failed to read fixtures") {
+        let name = entry.expect("Failed to read fixtures").file_name();
+        let name = name.to_string_lossy();
+        if !DAYS.iter().any(|(day, _)| *day == name) {
+            failures.push(format!("fixtures/{name}: no such day"));
+        }
+    }
+
+    for (day, assignments) in DAYS {
+        let fixtures = find_fixtures(day);
+        if fixtures.is_empty() {
+            failures.push(format!("{day}: no fixtures"));
+        }
+
+        for (input_path, answers_path) in fixtures {
+            let input = fs::read_to_string(&input_path).expect("Failed to read fixture");
+            let answers = fs::read_to_string(&answers_path)
+                .map_err(|error| error.to_string())
+                .and_then(|text| parse_answers(&text));
+            let answers = match answers {
+                Ok(answers) => answers,
+                Err(error) => {
+                    failures.push(format!("{}: {error}", answers_path.display()));
+                    continue;
+                }
+            };
+
+            for (index, expected) in answers {
+                let actual = match assignments[index](&input) {
+                    Ok(answer) => answer.to_string(),
+                    Err(error) => error.to_string(),
+                };
+                if actual != expected {
+                    failures.push(format!(
+                        "{} assignment{:02}: expected {expected}, got {actual}",
+                        input_path.display(),
+                        index + 1
+                    ));
+                }
+                checked += 1;
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+    assert!(checked > 0);
+}
+
+#[test]
+fn parse_answers_test() {
+    let answers = parse_answers("assignment01: 142\n\nassignment02:  -3 \n").unwrap();
+    assert_eq!(answers, vec![(0, "142".to_owned()), (1, "-3".to_owned())]);
+    assert!(parse_answers("assignment03: 1").is_err());
+    assert!(parse_answers("142").is_err());
+}
@@ -0,0 +1,40 @@
+//! Uses the parsed models of the days through the public API of the library.
+
+use std::cmp::Ordering;
+
+use aoc_2023::days::day03::{Grid, GridOptions};
+use aoc_2023::days::day07::{self, Hand, HandType};
+use aoc_2023::days::day08::{self, Network};
+
+#[test]
+fn hand_test() {
+    let hand = Hand::new("KTJJT".chars().collect(), 220);
+    let joker = Hand::new_with_joker("KTJJT".chars().collect(), 220);
+    assert_eq!(hand.hand_type(), HandType::TwoPair);
+    assert_eq!(joker.hand_type(), HandType::FourOfAKind);
+
+    let hands = day07::extract_input_01("32T3K 765\nKK677 28\n").unwrap();
+    assert_eq!(hands[1].bid(), 28);
+    assert_eq!(hands[0].cards(), &['3', '2', 'T', '3', 'K']);
+    assert_eq!(
+        hands[0].compare_with(&hands[1], day07::ORDERING),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn grid_test() {
+    let grid = Grid::parse("467\n..*\n.35\n", GridOptions::default()).unwrap();
+    assert_eq!((grid.get_row_size(), grid.get_col_size()), (3, 3));
+    assert_eq!(grid.get(1, 2).map(|cell| cell.value), Some('*'));
+    assert_eq!(grid.get_part_numbers(), Ok(vec![467, 35]));
+}
+
+#[test]
+fn network_test() {
+    let nodes = day08::get_nodes("L\n\nAAA = (BBB, AAA)\nBBB = (ZZZ, AAA)\nZZZ = (ZZZ, ZZZ)\n")
+        .expect("Failed to parse nodes");
+    let network = Network::from_nodes(&nodes);
+    let start = network.id("AAA").unwrap();
+    assert_eq!(network.walk(&['L'], start, |name| name == "ZZZ"), Ok(2));
+}